@@ -0,0 +1,83 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use htmlparser::{Stream, Tokenizer};
+
+const PARAGRAPH: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+    tempor incididunt ut labore et dolore magna aliqua. Съешь же ещё этих мягких французских булок. ";
+
+fn text_document() -> String {
+    let mut text = String::from("<html><body>");
+    for _ in 0..200 {
+        text.push_str("<p>");
+        text.push_str(&PARAGRAPH.repeat(10));
+        text.push_str("</p>");
+    }
+    text.push_str("</body></html>");
+    text
+}
+
+fn multiline_document() -> String {
+    let mut text = String::from("<html>\r\n<body>\r\n");
+    for _ in 0..200 {
+        text.push_str("\t<p>\r\n");
+        for _ in 0..10 {
+            text.push_str("\t\t");
+            text.push_str(PARAGRAPH);
+            text.push_str("\r\n");
+        }
+        text.push_str("\t</p>\r\n");
+    }
+    text.push_str("</body>\r\n</html>");
+    text
+}
+
+fn comment_document() -> String {
+    let mut text = String::from("<html><body>");
+    for _ in 0..200 {
+        text.push_str("<!-- ");
+        text.push_str(&PARAGRAPH.repeat(10));
+        text.push_str(" --><![CDATA[");
+        text.push_str(&PARAGRAPH.repeat(10));
+        text.push_str("]]>");
+    }
+    text.push_str("</body></html>");
+    text
+}
+
+fn consume(c: &mut Criterion) {
+    let text = PARAGRAPH.repeat(100) + "-->";
+
+    let mut group = c.benchmark_group("consume");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_function("consume_chars", |b| {
+        b.iter(|| {
+            let mut s = Stream::from(text.as_str());
+            s.consume_chars(|s, c| !(c == '-' && s.starts_with(b"-->")))
+                .unwrap()
+        })
+    });
+    group.bench_function("consume_until", |b| {
+        b.iter(|| {
+            let mut s = Stream::from(text.as_str());
+            s.consume_until(black_box(b"-->")).unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn tokenize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tokenize");
+    for (name, text) in [
+        ("text", text_document()),
+        ("multiline", multiline_document()),
+        ("comments", comment_document()),
+    ] {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| Tokenizer::from(black_box(text.as_str())).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, consume, tokenize);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use htmlparser::{Errors, LineIndex, Stream, Tokenizer};
+
+// Synthetic documents shaped like the real-world ones.
+const CORPORA: &[(&str, &str)] = &[
+    ("wikipedia", include_str!("corpora/wikipedia.html")),
+    ("spa", include_str!("corpora/spa.html")),
+    ("email", include_str!("corpora/email.html")),
+    ("svg", include_str!("corpora/image.svg")),
+];
+
+fn bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytes");
+    for &(name, text) in CORPORA {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), text, |b, text| {
+            b.iter(|| Tokenizer::from(black_box(text)).count())
+        });
+    }
+    group.finish();
+}
+
+fn tokens(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tokens");
+    for &(name, text) in CORPORA {
+        let count = Tokenizer::from(text).count();
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), text, |b, text| {
+            b.iter(|| {
+                for token in Tokenizer::from(black_box(text)) {
+                    black_box(token.unwrap());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn errors(c: &mut Criterion) {
+    let (_, text) = CORPORA[0];
+    let mut group = c.benchmark_group("errors");
+
+    // An error at the very end, so the position is computed from the whole text.
+    let last = format!("{}<!x>", text);
+    group.bench_function("last", |b| {
+        b.iter(|| Tokenizer::from(black_box(last.as_str())).find_map(|t| t.err()))
+    });
+
+    // An error on every line.
+    let broken = text.replace("<p>", "<p a=b>");
+    let count = Errors::new(&broken).count();
+    group.throughput(Throughput::Elements(count as u64));
+    group.bench_function("recovery", |b| {
+        b.iter(|| Errors::new(black_box(broken.as_str())).count())
+    });
+    group.finish();
+}
+
+fn text_pos(c: &mut Criterion) {
+    let (_, text) = CORPORA[0];
+    let offsets: Vec<usize> = Tokenizer::from(text)
+        .map(|t| t.unwrap().span().start())
+        .step_by(100)
+        .collect();
+
+    let mut group = c.benchmark_group("text_pos");
+    group.throughput(Throughput::Elements(offsets.len() as u64));
+    group.bench_function("gen_text_pos_from", |b| {
+        let s = Stream::from(text);
+        b.iter(|| {
+            for &offset in &offsets {
+                black_box(s.gen_text_pos_from(offset));
+            }
+        })
+    });
+    group.bench_function("line_index", |b| {
+        b.iter(|| {
+            let index = LineIndex::new(text);
+            for &offset in &offsets {
+                black_box(index.text_pos(offset));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bytes, tokens, errors, text_pos);
+criterion_main!(benches);
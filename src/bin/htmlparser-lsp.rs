@@ -0,0 +1,13 @@
+extern crate htmlparser as html;
+
+use std::io;
+use std::process;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(e) = html::LspServer::new().run(stdin.lock(), stdout.lock()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
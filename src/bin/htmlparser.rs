@@ -0,0 +1,241 @@
+extern crate htmlparser as html;
+
+use std::io::{self, Read, Write};
+use std::{env, fs, process};
+
+const USAGE: &str = "\
+Usage: htmlparser <command> [options] [file...]
+
+Commands:
+    tokens [--json] [file]  Prints the tokens with their positions
+    check [file...]         Checks the documents and prints all the errors
+    tree [file]             Prints an outline of the elements
+
+The standard input is read when no file or `-` is given.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let code = match run(&args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            2
+        }
+    };
+
+    process::exit(code);
+}
+
+fn run(args: &[String]) -> Result<i32, String> {
+    let (command, args) = match args.split_first() {
+        Some((command, args)) => (command.as_str(), args),
+        None => return Err(format!("no command provided\n\n{}", USAGE)),
+    };
+
+    match command {
+        "tokens" => {
+            let json = args.iter().any(|a| a == "--json");
+            let files: Vec<&String> = args.iter().filter(|a| *a != "--json").collect();
+            let source = Source::load(single_file(&files)?)?;
+            tokens(&source, json)
+        }
+        "check" => {
+            let mut code = 0;
+            if args.is_empty() {
+                code = check(&Source::load("-")?);
+            }
+
+            for path in args {
+                code = code.max(check(&Source::load(path)?));
+            }
+
+            Ok(code)
+        }
+        "tree" => {
+            let files: Vec<&String> = args.iter().collect();
+            let source = Source::load(single_file(&files)?)?;
+            tree(&source)
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        _ => Err(format!("unknown command '{}'\n\n{}", command, USAGE)),
+    }
+}
+
+fn single_file<'a>(files: &[&'a String]) -> Result<&'a str, String> {
+    match files {
+        [] => Ok("-"),
+        [file] => Ok(file.as_str()),
+        _ => Err("only a single file is allowed".to_string()),
+    }
+}
+
+struct Source {
+    name: String,
+    text: String,
+}
+
+impl Source {
+    fn load(path: &str) -> Result<Source, String> {
+        let mut text = String::new();
+        let name = if path == "-" {
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("failed to read the standard input: {}", e))?;
+            "<stdin>".to_string()
+        } else {
+            fs::File::open(path)
+                .and_then(|mut file| file.read_to_string(&mut text))
+                .map_err(|e| format!("failed to read '{}': {}", path, e))?;
+            path.to_string()
+        };
+
+        Ok(Source { name, text })
+    }
+
+    fn print_error(&self, e: &html::Error) {
+        let pos = e.cause().map_or_else(|| e.pos(), |c| c.pos());
+        eprintln!(
+            "{}:{}:{}: error: {}",
+            self.name,
+            pos.row,
+            pos.col,
+            e.message()
+        );
+    }
+}
+
+fn tokens(source: &Source, json: bool) -> Result<i32, String> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let index = html::LineIndex::new(&source.text);
+
+    for token in html::Tokenizer::from(source.text.as_str()) {
+        let token = match token {
+            Ok(token) => token,
+            Err(e) => {
+                source.print_error(&e);
+                return Ok(1);
+            }
+        };
+
+        let span = token.span();
+        let pos = index.text_pos(span.start());
+        let result = if json {
+            let line = serde_json::json!({
+                "row": pos.row,
+                "col": pos.col,
+                "token": token,
+            });
+            writeln!(out, "{}", line)
+        } else {
+            writeln!(out, "{}\t{}\t{:?}", pos, token_kind(&token), span.as_str())
+        };
+
+        result.map_err(|e| e.to_string())?;
+    }
+
+    Ok(0)
+}
+
+fn token_kind(token: &html::Token) -> &'static str {
+    match *token {
+        html::Token::Declaration { .. } => "Declaration",
+        html::Token::ProcessingInstruction { .. } => "ProcessingInstruction",
+        html::Token::ConditionalCommentStart { .. } => "ConditionalCommentStart",
+        html::Token::ConditionalCommentEnd { .. } => "ConditionalCommentEnd",
+        html::Token::Comment { .. } => "Comment",
+        html::Token::DtdStart { .. } => "DtdStart",
+        html::Token::EmptyDtd { .. } => "EmptyDtd",
+        html::Token::EntityDeclaration { .. } => "EntityDeclaration",
+        html::Token::DtdEnd { .. } => "DtdEnd",
+        html::Token::ElementStart { .. } => "ElementStart",
+        html::Token::Attribute { .. } => "Attribute",
+        html::Token::ElementEnd { .. } => "ElementEnd",
+        html::Token::Text { .. } => "Text",
+        html::Token::Cdata { .. } => "Cdata",
+    }
+}
+
+fn check(source: &Source) -> i32 {
+    let errors: Vec<_> = html::Errors::new(&source.text).collect();
+    for e in &errors {
+        source.print_error(e);
+    }
+
+    match errors.len() {
+        0 => 0,
+        1 => {
+            eprintln!("{}: 1 error", source.name);
+            1
+        }
+        n => {
+            eprintln!("{}: {} errors", source.name, n);
+            1
+        }
+    }
+}
+
+/// Elements that never have content, so they don't increase the depth.
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+fn tree(source: &Source) -> Result<i32, String> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut depth = 0;
+    let mut void = false;
+
+    for token in html::Tokenizer::from(source.text.as_str()) {
+        let result = match token {
+            Ok(html::Token::ElementStart { prefix, local, .. }) => {
+                void = prefix.is_empty()
+                    && VOID_TAGS
+                        .iter()
+                        .any(|tag| tag.eq_ignore_ascii_case(local.as_str()));
+                if prefix.is_empty() {
+                    writeln!(out, "{:indent$}{}", "", local, indent = depth * 2)
+                } else {
+                    writeln!(
+                        out,
+                        "{:indent$}{}:{}",
+                        "",
+                        prefix,
+                        local,
+                        indent = depth * 2
+                    )
+                }
+            }
+            Ok(html::Token::ElementEnd { end, .. }) => {
+                match end {
+                    html::ElementEnd::Open if !void => depth += 1,
+                    html::ElementEnd::Close(..) => depth = depth.saturating_sub(1),
+                    html::ElementEnd::Open | html::ElementEnd::Empty => {}
+                }
+                Ok(())
+            }
+            Ok(html::Token::ConditionalCommentStart { condition, .. }) => {
+                let result = writeln!(out, "{:indent$}[{}]", "", condition, indent = depth * 2);
+                depth += 1;
+                result
+            }
+            Ok(html::Token::ConditionalCommentEnd { .. }) => {
+                depth = depth.saturating_sub(1);
+                Ok(())
+            }
+            Ok(_) => Ok(()),
+            Err(e) => {
+                source.print_error(&e);
+                return Ok(1);
+            }
+        };
+
+        result.map_err(|e| e.to_string())?;
+    }
+
+    Ok(0)
+}
@@ -0,0 +1,409 @@
+use core::cmp::Ordering;
+
+use crate::{Error, StrSpan, Stream, StreamError, Token, Tokenizer, XmlByteExt};
+
+type StreamResult<T> = core::result::Result<T, StreamError>;
+
+/// A version of a conditional comment feature.
+///
+/// ```text
+/// <!--[if lte IE 5.5]>
+///                ---   - major.minor
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Version {
+    /// The major part of the version.
+    pub major: u32,
+    /// The minor part of the version.
+    pub minor: u32,
+}
+
+impl Version {
+    /// Constructs a new `Version`.
+    pub const fn new(major: u32, minor: u32) -> Version {
+        Version { major, minor }
+    }
+
+    // A condition only compares the parts it specifies,
+    // so `IE 5` matches both `5.0` and `5.5`, while `IE 5.5` matches only `5.5`.
+    fn compare(&self, major: u32, minor: Option<u32>) -> Ordering {
+        match minor {
+            Some(minor) => (self.major, self.minor).cmp(&(major, minor)),
+            None => self.major.cmp(&major),
+        }
+    }
+}
+
+/// A target against which conditional comments are evaluated.
+///
+/// Contains a list of the features (like `IE` or `mso`) supported by the target
+/// alongside with their versions.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{Profile, Version};
+///
+/// const IE8: Profile = Profile::new(&[("IE", Version::new(8, 0))]);
+/// const OUTLOOK_2016: Profile = Profile::new(&[("mso", Version::new(16, 0))]);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Profile<'a> {
+    features: &'a [(&'a str, Version)],
+}
+
+impl<'a> Profile<'a> {
+    /// A profile of a client that ignores conditional comments.
+    ///
+    /// Downlevel-hidden blocks are treated as regular comments
+    /// and downlevel-revealed blocks are always visible.
+    pub const MODERN: Profile<'static> = Profile { features: &[] };
+
+    /// Constructs a new `Profile` from a list of supported features.
+    ///
+    /// Feature names are compared case-insensitively.
+    pub const fn new(features: &'a [(&'a str, Version)]) -> Profile<'a> {
+        Profile { features }
+    }
+
+    /// Returns the version of the `feature`, if supported.
+    pub fn version(&self, feature: &str) -> Option<Version> {
+        self.features
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(feature))
+            .map(|(_, version)| *version)
+    }
+
+    /// Checks that the profile evaluates conditional comments at all.
+    pub fn supports_conditional_comments(&self) -> bool {
+        !self.features.is_empty()
+    }
+}
+
+/// A conditional comment expression.
+///
+/// ```text
+/// <!--[if (gt IE 5)&(lt IE 7)]>
+///      ----------------------   - condition
+/// ```
+///
+/// Supports features with optional versions (`IE`, `IE 5.5`, `mso 16`),
+/// the `lt`, `lte`, `gt` and `gte` comparisons, `true`, `false`,
+/// the `!`, `&` and `|` operators and parentheses.
+/// `!` binds tighter than `&`, which binds tighter than `|`.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{Condition, Profile, Version};
+///
+/// const IE8: Profile = Profile::new(&[("IE", Version::new(8, 0))]);
+///
+/// let condition = Condition::parse("if lte IE 7").unwrap();
+/// assert!(!condition.evaluate(&IE8));
+///
+/// let condition = Condition::parse("if (IE 8) | mso").unwrap();
+/// assert!(condition.evaluate(&IE8));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Condition<'a> {
+    text: &'a str,
+}
+
+impl<'a> Condition<'a> {
+    /// Parses a condition, including the leading `if`.
+    ///
+    /// # Errors
+    ///
+    /// - `InvalidString` - if the condition doesn't start with `if`
+    /// - `InvalidName` - if a feature name is missing
+    /// - `InvalidChar`, `InvalidCharMultiple` - on an unexpected character
+    /// - `UnexpectedEndOfStream`
+    pub fn parse(text: &'a str) -> StreamResult<Condition<'a>> {
+        Self::parse_stream(Stream::from(text))
+    }
+
+    // Like `parse`, but reports errors at the condition position in the stream text.
+    pub(crate) fn parse_at(stream: &Stream<'a>, condition: StrSpan<'a>) -> StreamResult<Self> {
+        let mut s = Stream::from_substr(stream.span().as_str(), condition.range());
+        s.set_column_unit(stream.column_unit());
+        Self::parse_stream(s)
+    }
+
+    fn parse_stream(mut s: Stream<'a>) -> StreamResult<Condition<'a>> {
+        let start = s.pos();
+        eval_condition(&mut s, &Profile::MODERN)?;
+        Ok(Condition {
+            text: s.slice_back(start).as_str(),
+        })
+    }
+
+    /// Returns the condition text.
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Evaluates the condition against the `profile`.
+    pub fn evaluate(&self, profile: &Profile) -> bool {
+        // The text was already validated by `parse`.
+        eval_condition(&mut Stream::from(self.text), profile).unwrap_or(false)
+    }
+}
+
+// condition ::= S? 'if' S expr S?
+fn eval_condition(s: &mut Stream, profile: &Profile) -> StreamResult<bool> {
+    s.skip_spaces();
+    s.skip_string(b"if")?;
+    s.consume_spaces()?;
+    let value = eval_or(s, profile)?;
+    s.skip_spaces();
+
+    if !s.at_end() {
+        static EXPECTED: &[u8] = b"&|";
+        let c = s.curr_byte_unchecked();
+        return Err(StreamError::InvalidCharMultiple(
+            c,
+            EXPECTED,
+            s.gen_text_pos(),
+        ));
+    }
+
+    Ok(value)
+}
+
+// or ::= and (S? '|' S? and)*
+fn eval_or(s: &mut Stream, profile: &Profile) -> StreamResult<bool> {
+    let mut value = eval_and(s, profile)?;
+    loop {
+        s.skip_spaces();
+        if !s.try_consume_byte(b'|') {
+            return Ok(value);
+        }
+
+        let rhs = eval_and(s, profile)?;
+        value = value || rhs;
+    }
+}
+
+// and ::= unary (S? '&' S? unary)*
+fn eval_and(s: &mut Stream, profile: &Profile) -> StreamResult<bool> {
+    let mut value = eval_unary(s, profile)?;
+    loop {
+        s.skip_spaces();
+        if !s.try_consume_byte(b'&') {
+            return Ok(value);
+        }
+
+        let rhs = eval_unary(s, profile)?;
+        value = value && rhs;
+    }
+}
+
+// unary ::= '!' S? unary | '(' S? or S? ')' | term
+fn eval_unary(s: &mut Stream, profile: &Profile) -> StreamResult<bool> {
+    s.skip_spaces();
+    match s.curr_byte()? {
+        b'!' => {
+            s.advance(1);
+            Ok(!eval_unary(s, profile)?)
+        }
+        b'(' => {
+            s.advance(1);
+            let value = eval_or(s, profile)?;
+            s.skip_spaces();
+            s.consume_byte(b')')?;
+            Ok(value)
+        }
+        _ => eval_term(s, profile),
+    }
+}
+
+// term ::= 'true' | 'false' | (('lt' | 'lte' | 'gt' | 'gte') S)? Feature (S Version)?
+fn eval_term(s: &mut Stream, profile: &Profile) -> StreamResult<bool> {
+    let mut word = consume_word(s)?;
+
+    let comparison = match word {
+        "true" => return Ok(true),
+        "false" => return Ok(false),
+        "lt" => Some([Ordering::Less, Ordering::Less]),
+        "lte" => Some([Ordering::Less, Ordering::Equal]),
+        "gt" => Some([Ordering::Greater, Ordering::Greater]),
+        "gte" => Some([Ordering::Greater, Ordering::Equal]),
+        _ => None,
+    };
+
+    if comparison.is_some() {
+        s.consume_spaces()?;
+        word = consume_word(s)?;
+    }
+
+    let version = if comparison.is_some() {
+        s.consume_spaces()?;
+        Some(consume_version(s)?)
+    } else {
+        // A version is optional for an equality check.
+        let mut tmp = *s;
+        tmp.skip_spaces();
+        if !tmp.at_end() && tmp.curr_byte_unchecked().is_xml_digit() {
+            *s = tmp;
+            Some(consume_version(s)?)
+        } else {
+            None
+        }
+    };
+
+    let actual = match profile.version(word) {
+        Some(v) => v,
+        None => return Ok(false),
+    };
+
+    let value = match version {
+        Some((major, minor)) => {
+            let ord = actual.compare(major, minor);
+            match comparison {
+                Some(expected) => expected.contains(&ord),
+                None => ord == Ordering::Equal,
+            }
+        }
+        None => true,
+    };
+
+    Ok(value)
+}
+
+fn consume_word<'a>(s: &mut Stream<'a>) -> StreamResult<&'a str> {
+    let word = s.consume_bytes(|_, c| c.is_xml_letter());
+    if word.is_empty() {
+        if s.at_end() {
+            return Err(StreamError::UnexpectedEndOfStream(s.gen_text_pos()));
+        }
+
+        return Err(StreamError::InvalidName(s.gen_text_pos()));
+    }
+
+    Ok(word.as_str())
+}
+
+// Version ::= [0-9]+ ('.' [0-9]+)?
+fn consume_version(s: &mut Stream) -> StreamResult<(u32, Option<u32>)> {
+    let major = consume_number(s)?;
+    let minor = if s.try_consume_byte(b'.') {
+        Some(consume_number(s)?)
+    } else {
+        None
+    };
+
+    Ok((major, minor))
+}
+
+fn consume_number(s: &mut Stream) -> StreamResult<u32> {
+    static EXPECTED: &[u8] = b"0123456789";
+
+    let c = s.curr_byte()?;
+    if !c.is_xml_digit() {
+        return Err(StreamError::InvalidCharMultiple(
+            c,
+            EXPECTED,
+            s.gen_text_pos(),
+        ));
+    }
+
+    // Overflowing versions are clamped, since they can't match anything anyway.
+    let digits = s.consume_bytes(|_, c| c.is_xml_digit());
+    Ok(digits.as_str().parse().unwrap_or(u32::MAX))
+}
+
+/// Checks that a `ConditionalCommentStart` span is a downlevel-hidden one.
+///
+/// ```text
+/// <!--[if IE 8]>        - downlevel-hidden
+/// <![if !IE]>           - downlevel-revealed
+/// <!--[if !IE]><!-->    - downlevel-revealed
+/// ```
+fn is_downlevel_hidden(span: &str) -> bool {
+    span.starts_with("<!--") && !span.ends_with("-->")
+}
+
+/// A tokenizer adapter that removes the content hidden for a specific [`Profile`].
+///
+/// The conditional comment tokens themselves are removed as well.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{ConditionalFilter, Profile, Token, Tokenizer, Version};
+///
+/// let text = "<p><!--[if IE]><b/><![endif]--><![if !IE]><i/><![endif]></p>";
+/// const IE8: Profile = Profile::new(&[("IE", Version::new(8, 0))]);
+///
+/// let names: Vec<_> = ConditionalFilter::new(Tokenizer::from(text), IE8)
+///     .filter_map(|t| match t {
+///         Ok(Token::ElementStart { local, .. }) => Some(local.as_str()),
+///         _ => None,
+///     })
+///     .collect();
+/// assert_eq!(names, vec!["p", "b"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConditionalFilter<'a, 'p> {
+    tokenizer: Tokenizer<'a>,
+    profile: Profile<'p>,
+    hidden_by: Option<StrSpan<'a>>,
+}
+
+impl<'a, 'p> ConditionalFilter<'a, 'p> {
+    /// Constructs a new `ConditionalFilter`.
+    pub fn new(tokenizer: Tokenizer<'a>, profile: Profile<'p>) -> Self {
+        ConditionalFilter {
+            tokenizer,
+            profile,
+            hidden_by: None,
+        }
+    }
+
+    /// Checks that the content is currently hidden.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden_by.is_some()
+    }
+
+    fn is_visible(&self, condition: StrSpan<'a>, span: StrSpan<'a>) -> Result<bool, Error> {
+        let stream = self.tokenizer.stream();
+
+        let condition = Condition::parse_at(&stream, condition).map_err(|e| {
+            Error::InvalidConditionalComment(e, stream.gen_text_pos_from(span.start()))
+        })?;
+
+        if self.profile.supports_conditional_comments() {
+            Ok(condition.evaluate(&self.profile))
+        } else {
+            Ok(!is_downlevel_hidden(span.as_str()))
+        }
+    }
+}
+
+impl<'a> Iterator for ConditionalFilter<'a, '_> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.tokenizer.next()? {
+                Ok(Token::ConditionalCommentStart { condition, span }) => {
+                    if self.hidden_by.is_none() {
+                        match self.is_visible(condition, span) {
+                            Ok(true) => {}
+                            Ok(false) => self.hidden_by = Some(span),
+                            Err(e) => return Some(Err(e)),
+                        }
+                    }
+                }
+                Ok(Token::ConditionalCommentEnd { start, .. }) => {
+                    if self.hidden_by == Some(start) {
+                        self.hidden_by = None;
+                    }
+                }
+                Ok(_) if self.hidden_by.is_some() => {}
+                t => return Some(t),
+            }
+        }
+    }
+}
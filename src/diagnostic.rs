@@ -0,0 +1,314 @@
+use core::fmt;
+
+#[cfg(feature = "std")]
+use crate::LineIndex;
+use crate::{ColumnUnit, Error, StreamError, Tokenizer, MAX_CONDITIONAL_DEPTH};
+
+/// A human-readable error report with a source snippet.
+///
+/// Shows the line with the error, underlines the part of the token parsed so far,
+/// points at the exact error position and suggests a fix when possible.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::Tokenizer;
+///
+/// let text = "<p>\n<a href=\"x\" class=main>\n</p>";
+/// let error = Tokenizer::from(text).find_map(|t| t.err()).unwrap();
+///
+/// assert_eq!(
+///     error.diagnostic(text).to_string(),
+///     "error: invalid attribute
+///  --> 2:19
+///   |
+/// 2 | <a href=\"x\" class=main>
+///   |            -------^ expected quote mark not 'm'
+///   |
+///   = help: attribute values must be quoted with '\"' or '''
+/// "
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Diagnostic<'a> {
+    text: &'a str,
+    error: Error,
+    path: Option<&'a str>,
+    column_unit: ColumnUnit,
+}
+
+impl Error {
+    /// Returns a [`Diagnostic`] for the error in the `text`.
+    pub fn diagnostic<'a>(&self, text: &'a str) -> Diagnostic<'a> {
+        Diagnostic::new(text, *self)
+    }
+}
+
+impl<'a> Diagnostic<'a> {
+    /// Constructs a new `Diagnostic`.
+    ///
+    /// `text` must be the same text the error was produced from.
+    pub fn new(text: &'a str, error: Error) -> Self {
+        Diagnostic {
+            text,
+            error,
+            path: None,
+            column_unit: ColumnUnit::Chars,
+        }
+    }
+
+    /// Sets the path of the document shown next to the position.
+    pub fn with_path(mut self, path: &'a str) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Sets the unit in which the error columns are counted, characters by default.
+    ///
+    /// Must be the unit the tokenizer was configured with,
+    /// see [`Tokenizer::with_column_unit`](crate::Tokenizer::with_column_unit).
+    pub fn with_column_unit(mut self, unit: ColumnUnit) -> Self {
+        self.column_unit = unit;
+        self
+    }
+
+    /// Returns the error.
+    pub fn error(&self) -> Error {
+        self.error
+    }
+
+    fn line(&self, row: u32) -> &'a str {
+        let line = self
+            .text
+            .split('\n')
+            .nth(row.saturating_sub(1) as usize)
+            .unwrap_or("");
+        line.trim_end_matches('\r')
+    }
+
+    fn fmt_label(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.error.cause() {
+            Some(cause) => cause.fmt_message(f),
+            None => write!(f, "{}", self.error.title()),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let start = self.error.pos();
+        let pos = self.error.cause().map_or(start, |c| c.pos());
+
+        // Underline the token from its start when it's on the same line.
+        let underline_from = if start.row == pos.row && start.col < pos.col {
+            start.col
+        } else {
+            pos.col
+        };
+
+        let line = self.line(pos.row);
+        let width = digits(pos.row);
+
+        writeln!(f, "error: {}", self.error.title())?;
+        match self.path {
+            Some(path) => writeln!(f, "{:w$}--> {}:{}", "", path, pos, w = width)?,
+            None => writeln!(f, "{:w$}--> {}", "", pos, w = width)?,
+        }
+        writeln!(f, "{:w$} |", "", w = width)?;
+        writeln!(f, "{} | {}", pos.row, line)?;
+        write!(f, "{:w$} | ", "", w = width)?;
+
+        let offset = |col: u32| {
+            let count = col.saturating_sub(1) as usize;
+            self.column_unit.offset(line, count)
+        };
+        let (from, to) = (offset(underline_from), offset(pos.col));
+
+        // Keep tabs, so the marker is aligned with the line above.
+        for c in line[..from].chars() {
+            match c {
+                '\t' => write!(f, "\t")?,
+                _ => write!(f, " ")?,
+            }
+        }
+        for _ in line[from..to].chars() {
+            write!(f, "-")?;
+        }
+        write!(f, "^ ")?;
+        self.fmt_label(f)?;
+        writeln!(f)?;
+
+        if let Some(help) = Help::new(self.error) {
+            writeln!(f, "{:w$} |", "", w = width)?;
+            writeln!(f, "{:w$} = help: {}", "", help, w = width)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A suggestion on how to fix an error.
+pub(crate) enum Help {
+    Text(&'static str),
+    /// A missing string, like `'>'`.
+    Missing(&'static str),
+    /// Conditional comments are nested too deeply.
+    TooDeep,
+}
+
+impl Help {
+    pub(crate) fn new(error: Error) -> Option<Help> {
+        let cause = match error.cause() {
+            Some(cause) => cause,
+            None => {
+                return Some(Help::Text(
+                    "if this is a text, escape '<' as '&lt;' and '&' as '&amp;'",
+                ))
+            }
+        };
+
+        let text = match cause {
+            StreamError::UnexpectedEndOfStream(_) => match error {
+                Error::InvalidComment(..) | Error::InvalidConditionalComment(..) => {
+                    "did you forget to close the comment?"
+                }
+                Error::InvalidCdata(..) => "did you forget to close the CDATA with ']]>'?",
+                Error::InvalidElement(..) | Error::InvalidAttribute(..) => {
+                    "did you forget to close the tag with '>'?"
+                }
+                _ => "the document ended before the token was closed",
+            },
+            StreamError::InvalidName(_) => "names must start with a letter, '_' or ':'",
+            StreamError::NonXmlChar(..) => {
+                "remove the character or use a character reference instead"
+            }
+            StreamError::InvalidQuote(..) => "attribute values must be quoted with '\"' or '''",
+            StreamError::InvalidSpace(..) => "attributes must be separated by spaces",
+            StreamError::InvalidString(expected, _) => match error {
+                Error::InvalidComment(..) => "did you forget to close the comment?",
+                _ => return Some(Help::Missing(expected)),
+            },
+            StreamError::InvalidReference(_) => "escape '&' as '&amp;'",
+            StreamError::InvalidExternalID(_) => "use 'SYSTEM \"uri\"' or 'PUBLIC \"id\" \"uri\"'",
+            StreamError::InvalidCommentData(_) => "replace '--' inside the comment with '- -'",
+            StreamError::InvalidCommentEnd(_) => "remove the '-' before '-->'",
+            StreamError::InvalidCharacterData(_) => "escape '>' as '&gt;'",
+            StreamError::UnexpectedConditionalCommentEnd(_) => {
+                "remove it or add a matching '<!--[if ...]>' before"
+            }
+            StreamError::UnclosedConditionalComment(_) => {
+                "did you forget to close it with '<![endif]-->'?"
+            }
+            StreamError::ConditionalCommentTooDeep(_) => return Some(Help::TooDeep),
+            // The message already tells what is expected.
+            StreamError::InvalidChar(..) | StreamError::InvalidCharMultiple(..) => return None,
+        };
+
+        Some(Help::Text(text))
+    }
+}
+
+impl fmt::Display for Help {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Help::Text(text) => write!(f, "{}", text),
+            Help::Missing(expected) => write!(f, "did you forget '{}'?", expected),
+            Help::TooDeep => write!(
+                f,
+                "conditional comments can be nested at most {} levels deep",
+                MAX_CONDITIONAL_DEPTH
+            ),
+        }
+    }
+}
+
+/// An iterator over all the errors in a text.
+///
+/// The tokenizer stops on the first error, so parsing is resumed
+/// from the next `<` after the failed token, with the same conditional comments.
+/// Since the structure around an error is unknown, any content is allowed after it,
+/// like multiple root elements.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::Errors;
+///
+/// let errors: Vec<_> = Errors::new("<p a=b>\n<!x>\n</p>").collect();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[1].to_string(), "unknown token at 2:1");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Errors<'a> {
+    text: &'a str,
+    tokenizer: Option<Tokenizer<'a>>,
+}
+
+impl<'a> Errors<'a> {
+    /// Constructs a new `Errors` iterator.
+    pub fn new(text: &'a str) -> Self {
+        Errors {
+            text,
+            tokenizer: Some(Tokenizer::from(text)),
+        }
+    }
+
+    /// Sets the unit used to count columns in error positions.
+    ///
+    /// Columns are counted in characters by default.
+    pub fn with_column_unit(mut self, unit: ColumnUnit) -> Self {
+        self.tokenizer = self.tokenizer.map(|t| t.with_column_unit(unit));
+        self
+    }
+
+    /// Sets the index used to compute error positions, instead of rescanning the text.
+    ///
+    /// The index must be built from the same text.
+    #[cfg(feature = "std")]
+    pub fn with_line_index(mut self, index: &'a LineIndex<'a>) -> Self {
+        self.tokenizer = self.tokenizer.map(|t| t.with_line_index(index));
+        self
+    }
+}
+
+impl Iterator for Errors<'_> {
+    type Item = Error;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let tokenizer = self.tokenizer.as_mut()?;
+            // The tokenizer state is lost on errors.
+            let mut resumed = tokenizer.clone();
+            let start = tokenizer.stream().pos();
+            match tokenizer.next() {
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    let next = self.text[start..]
+                        .char_indices()
+                        .skip(1)
+                        .find(|&(_, c)| c == '<');
+                    self.tokenizer = next.map(|(idx, _)| {
+                        resumed.resume(idx);
+                        resumed
+                    });
+
+                    return Some(e);
+                }
+                None => {
+                    self.tokenizer = None;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+fn digits(mut n: u32) -> usize {
+    let mut count = 1;
+    while n >= 10 {
+        n /= 10;
+        count += 1;
+    }
+
+    count
+}
@@ -0,0 +1,183 @@
+use crate::{ElementEnd, Error, StrSpan, Stream, StreamError, Token, Tokenizer};
+
+/// An element start tag with all its attributes.
+///
+/// Returned by [`Tokenizer::next_element`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ElementStartTag<'a> {
+    prefix: StrSpan<'a>,
+    local: StrSpan<'a>,
+    span: StrSpan<'a>,
+    self_closing: bool,
+    attributes: Stream<'a>,
+}
+
+impl<'a> ElementStartTag<'a> {
+    /// Returns the element prefix.
+    #[inline]
+    pub fn prefix(&self) -> StrSpan<'a> {
+        self.prefix
+    }
+
+    /// Returns the element local name.
+    #[inline]
+    pub fn local(&self) -> StrSpan<'a> {
+        self.local
+    }
+
+    /// Returns the whole tag, from `<` to `>` or `/>`.
+    #[inline]
+    pub fn span(&self) -> StrSpan<'a> {
+        self.span
+    }
+
+    /// Checks that the tag ends with `/>`.
+    #[inline]
+    pub fn is_self_closing(&self) -> bool {
+        self.self_closing
+    }
+
+    /// Returns an iterator over the attributes.
+    ///
+    /// Attributes are parsed on each call, without allocations.
+    #[inline]
+    pub fn attributes(&self) -> Attributes<'a> {
+        Attributes {
+            stream: self.attributes,
+        }
+    }
+
+    /// Returns the value of the first attribute with the qualified `name`,
+    /// like `class` or `xlink:href`.
+    ///
+    /// Returns `Some("")` for an attribute without a value.
+    pub fn attribute(&self, name: &str) -> Option<StrSpan<'a>> {
+        let (prefix, local) = match name.find(':') {
+            Some(idx) => (&name[..idx], &name[idx + 1..]),
+            None => ("", name),
+        };
+
+        self.attributes()
+            .find(|attr| attr.prefix.as_str() == prefix && attr.local.as_str() == local)
+            .map(|attr| {
+                attr.value
+                    .unwrap_or_else(|| StrSpan::with_start("", attr.local.end()))
+            })
+    }
+}
+
+/// An element attribute.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Attribute<'a> {
+    pub prefix: StrSpan<'a>,
+    pub local: StrSpan<'a>,
+    pub value: Option<StrSpan<'a>>,
+    pub span: StrSpan<'a>,
+}
+
+/// An iterator over the attributes of an [`ElementStartTag`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Attributes<'a> {
+    stream: Stream<'a>,
+}
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = Attribute<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stream.at_end() {
+            return None;
+        }
+
+        // The tag was already validated, so only the trailing spaces can fail.
+        match Tokenizer::parse_attribute(&mut self.stream) {
+            Ok(Token::Attribute {
+                prefix,
+                local,
+                value,
+                span,
+            }) => Some(Attribute {
+                prefix,
+                local,
+                value,
+                span,
+            }),
+            _ => {
+                self.stream.jump_to_end();
+                None
+            }
+        }
+    }
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Returns the next element start tag, skipping all the other tokens.
+    ///
+    /// The tag is consumed up to its end, so the next token is the element content.
+    /// Can be freely mixed with the token iterator, but a start tag
+    /// that was already returned by it is not returned again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::from("<p>Hi <a href='/home' hidden>there</a><br/></p>");
+    /// let mut names = Vec::new();
+    /// while let Some(tag) = tokenizer.next_element() {
+    ///     let tag = tag.unwrap();
+    ///     names.push(tag.local().as_str());
+    ///     if tag.local() == "a" {
+    ///         assert_eq!(tag.attribute("href").unwrap(), "/home");
+    ///         assert_eq!(tag.attribute("hidden").unwrap(), "");
+    ///         assert_eq!(tag.attributes().count(), 2);
+    ///     }
+    /// }
+    /// assert_eq!(names, vec!["p", "a", "br"]);
+    /// ```
+    pub fn next_element(&mut self) -> Option<Result<ElementStartTag<'a>, Error>> {
+        loop {
+            match self.next()? {
+                Ok(Token::ElementStart {
+                    prefix,
+                    local,
+                    span,
+                }) => return Some(self.finish_start_tag(prefix, local, span)),
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    fn finish_start_tag(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        start: StrSpan<'a>,
+    ) -> Result<ElementStartTag<'a>, Error> {
+        let text = self.stream.span().as_str();
+        let attributes_start = self.stream.pos();
+
+        loop {
+            match self.next() {
+                Some(Ok(Token::ElementEnd { end, span })) => {
+                    return Ok(ElementStartTag {
+                        prefix,
+                        local,
+                        span: StrSpan::from_substr(text, start.start(), span.end()),
+                        self_closing: end == ElementEnd::Empty,
+                        attributes: Stream::from_substr(text, attributes_start..span.start()),
+                    });
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => {
+                    let pos = self.stream.gen_text_pos_from(start.start());
+                    let e = StreamError::UnexpectedEndOfStream(self.stream.gen_text_pos());
+                    return Err(Error::InvalidElement(e, pos));
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use core::ops::Range;
 use core::{fmt, str};
 #[cfg(feature = "std")]
 use std::error;
@@ -5,6 +6,7 @@ use std::error;
 /// An XML parser errors.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Error {
     InvalidDeclaration(StreamError, TextPos),
     InvalidConditionalComment(StreamError, TextPos),
@@ -36,48 +38,107 @@ impl Error {
             Error::UnknownToken(pos) => pos,
         }
     }
+
+    /// Returns the error byte offset.
+    ///
+    /// Points to the start of the token that failed to parse.
+    pub fn offset(&self) -> usize {
+        self.pos().offset
+    }
+
+    /// Returns the byte range of the token that failed to parse, up to the exact error position.
+    ///
+    /// The range is empty when the error is at the start of the token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::Tokenizer;
+    ///
+    /// let text = "<p a=b/>";
+    /// let error = Tokenizer::from(text).find_map(|t| t.err()).unwrap();
+    /// assert_eq!(error.range(), 2..5);
+    /// assert_eq!(&text[error.range()], " a=");
+    /// ```
+    pub fn range(&self) -> Range<usize> {
+        let start = self.offset();
+        let end = match self.cause() {
+            Some(cause) if cause.offset() > start => cause.offset(),
+            _ => start,
+        };
+
+        start..end
+    }
+
+    /// Returns the underlying stream error, if any.
+    pub fn cause(&self) -> Option<StreamError> {
+        match *self {
+            Error::InvalidDeclaration(cause, _) => Some(cause),
+            Error::InvalidConditionalComment(cause, _) => Some(cause),
+            Error::InvalidComment(cause, _) => Some(cause),
+            Error::InvalidPI(cause, _) => Some(cause),
+            Error::InvalidDoctype(cause, _) => Some(cause),
+            Error::InvalidEntity(cause, _) => Some(cause),
+            Error::InvalidElement(cause, _) => Some(cause),
+            Error::InvalidAttribute(cause, _) => Some(cause),
+            Error::InvalidCdata(cause, _) => Some(cause),
+            Error::InvalidCharData(cause, _) => Some(cause),
+            Error::UnknownToken(_) => None,
+        }
+    }
+
+    /// Returns a short description of the error, without the position and the cause.
+    pub fn title(&self) -> &'static str {
+        match *self {
+            Error::InvalidDeclaration(..) => "invalid XML declaration",
+            Error::InvalidConditionalComment(..) => "invalid conditional comment",
+            Error::InvalidComment(..) => "invalid comment",
+            Error::InvalidPI(..) => "invalid processing instruction",
+            Error::InvalidDoctype(..) => "invalid DTD",
+            Error::InvalidEntity(..) => "invalid DTD entity",
+            Error::InvalidElement(..) => "invalid element",
+            Error::InvalidAttribute(..) => "invalid attribute",
+            Error::InvalidCdata(..) => "invalid CDATA",
+            Error::InvalidCharData(..) => "invalid character data",
+            Error::UnknownToken(..) => "unknown token",
+        }
+    }
+
+    /// Returns the error message without the positions, for when they are shown separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::Tokenizer;
+    ///
+    /// let error = Tokenizer::from("<p a=b/>").find_map(|t| t.err()).unwrap();
+    /// assert_eq!(error.message().to_string(), "invalid attribute: expected quote mark not 'b'");
+    /// ```
+    pub fn message(&self) -> impl fmt::Display {
+        ErrorMessage(*self)
+    }
+}
+
+/// An error message without the positions.
+struct ErrorMessage(Error);
+
+impl fmt::Display for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.title())?;
+        if let Some(cause) = self.0.cause() {
+            write!(f, ": ")?;
+            cause.fmt_message(f)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            Error::InvalidDeclaration(ref cause, pos) => {
-                write!(f, "invalid XML declaration at {} cause {}", pos, cause)
-            }
-            Error::InvalidConditionalComment(ref cause, pos) => {
-                write!(f, "invalid conditional comment at {} cause {}", pos, cause)
-            }
-            Error::InvalidComment(ref cause, pos) => {
-                write!(f, "invalid comment at {} cause {}", pos, cause)
-            }
-            Error::InvalidPI(ref cause, pos) => {
-                write!(
-                    f,
-                    "invalid processing instruction at {} cause {}",
-                    pos, cause
-                )
-            }
-            Error::InvalidDoctype(ref cause, pos) => {
-                write!(f, "invalid DTD at {} cause {}", pos, cause)
-            }
-            Error::InvalidEntity(ref cause, pos) => {
-                write!(f, "invalid DTD entity at {} cause {}", pos, cause)
-            }
-            Error::InvalidElement(ref cause, pos) => {
-                write!(f, "invalid element at {} cause {}", pos, cause)
-            }
-            Error::InvalidAttribute(ref cause, pos) => {
-                write!(f, "invalid attribute at {} cause {}", pos, cause)
-            }
-            Error::InvalidCdata(ref cause, pos) => {
-                write!(f, "invalid CDATA at {} cause {}", pos, cause)
-            }
-            Error::InvalidCharData(ref cause, pos) => {
-                write!(f, "invalid character data at {} cause {}", pos, cause)
-            }
-            Error::UnknownToken(pos) => {
-                write!(f, "unknown token at {}", pos)
-            }
+        match self.cause() {
+            Some(cause) => write!(f, "{} at {} cause {}", self.title(), self.pos(), cause),
+            None => write!(f, "{} at {}", self.title(), self.pos()),
         }
     }
 }
@@ -91,15 +152,16 @@ impl error::Error for Error {
 
 /// A stream parser errors.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum StreamError {
     /// The steam ended earlier than we expected.
     ///
     /// Should only appear on invalid input data.
     /// Errors in a valid XML should be handled by errors below.
-    UnexpectedEndOfStream,
+    UnexpectedEndOfStream(TextPos),
 
     /// An invalid name.
-    InvalidName,
+    InvalidName(TextPos),
 
     /// A non-XML character has occurred.
     ///
@@ -132,43 +194,80 @@ pub enum StreamError {
     InvalidString(&'static str, TextPos),
 
     /// An invalid reference.
-    InvalidReference,
+    InvalidReference(TextPos),
 
     /// An invalid ExternalID in the DTD.
-    InvalidExternalID,
+    InvalidExternalID(TextPos),
 
     /// Comment cannot contain `--`.
-    InvalidCommentData,
+    InvalidCommentData(TextPos),
 
     /// Comment cannot end with `-`.
-    InvalidCommentEnd,
+    InvalidCommentEnd(TextPos),
 
     /// A Character Data node contains an invalid data.
     ///
     /// Currently, only `]]>` is not allowed.
-    InvalidCharacterData,
+    InvalidCharacterData(TextPos),
+
+    /// A conditional comment end without a matching start.
+    UnexpectedConditionalCommentEnd(TextPos),
+
+    /// A conditional comment start without a matching end.
+    UnclosedConditionalComment(TextPos),
+
+    /// Conditional comments are nested too deeply.
+    ConditionalCommentTooDeep(TextPos),
 }
 
-impl fmt::Display for StreamError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl StreamError {
+    /// Returns the exact position of the error.
+    pub fn pos(&self) -> TextPos {
+        match *self {
+            StreamError::UnexpectedEndOfStream(pos)
+            | StreamError::InvalidName(pos)
+            | StreamError::NonXmlChar(_, pos)
+            | StreamError::InvalidChar(_, _, pos)
+            | StreamError::InvalidCharMultiple(_, _, pos)
+            | StreamError::InvalidQuote(_, pos)
+            | StreamError::InvalidSpace(_, pos)
+            | StreamError::InvalidString(_, pos)
+            | StreamError::InvalidReference(pos)
+            | StreamError::InvalidExternalID(pos)
+            | StreamError::InvalidCommentData(pos)
+            | StreamError::InvalidCommentEnd(pos)
+            | StreamError::InvalidCharacterData(pos)
+            | StreamError::UnexpectedConditionalCommentEnd(pos)
+            | StreamError::UnclosedConditionalComment(pos)
+            | StreamError::ConditionalCommentTooDeep(pos) => pos,
+        }
+    }
+
+    /// Returns the exact byte offset of the error.
+    pub fn offset(&self) -> usize {
+        self.pos().offset
+    }
+
+    /// Writes the error message without the position.
+    pub(crate) fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            StreamError::UnexpectedEndOfStream => {
+            StreamError::UnexpectedEndOfStream(_) => {
                 write!(f, "unexpected end of stream")
             }
-            StreamError::InvalidName => {
+            StreamError::InvalidName(_) => {
                 write!(f, "invalid name token")
             }
-            StreamError::NonXmlChar(c, pos) => {
-                write!(f, "a non-XML character {:?} found at {}", c, pos)
+            StreamError::NonXmlChar(c, _) => {
+                write!(f, "a non-XML character {:?} found", c)
             }
-            StreamError::InvalidChar(actual, expected, pos) => {
+            StreamError::InvalidChar(actual, expected, _) => {
                 write!(
                     f,
-                    "expected '{}' not '{}' at {}",
-                    expected as char, actual as char, pos
+                    "expected '{}' not '{}'",
+                    expected as char, actual as char
                 )
             }
-            StreamError::InvalidCharMultiple(actual, expected, pos) => {
+            StreamError::InvalidCharMultiple(actual, expected, _) => {
                 let mut expected_iter = expected.iter().peekable();
 
                 write!(f, "expected ")?;
@@ -178,36 +277,55 @@ impl fmt::Display for StreamError {
                         write!(f, ", ")?;
                     }
                 }
-                write!(f, " not '{}' at {}", actual as char, pos)
+                write!(f, " not '{}'", actual as char)
             }
-            StreamError::InvalidQuote(c, pos) => {
-                write!(f, "expected quote mark not '{}' at {}", c as char, pos)
+            StreamError::InvalidQuote(c, _) => {
+                write!(f, "expected quote mark not '{}'", c as char)
             }
-            StreamError::InvalidSpace(c, pos) => {
-                write!(f, "expected space not '{}' at {}", c as char, pos)
+            StreamError::InvalidSpace(c, _) => {
+                write!(f, "expected space not '{}'", c as char)
             }
-            StreamError::InvalidString(expected, pos) => {
-                write!(f, "expected '{}' at {}", expected, pos)
+            StreamError::InvalidString(expected, _) => {
+                write!(f, "expected '{}'", expected)
             }
-            StreamError::InvalidReference => {
+            StreamError::InvalidReference(_) => {
                 write!(f, "invalid reference")
             }
-            StreamError::InvalidExternalID => {
+            StreamError::InvalidExternalID(_) => {
                 write!(f, "invalid ExternalID")
             }
-            StreamError::InvalidCommentData => {
+            StreamError::InvalidCommentData(_) => {
                 write!(f, "'--' is not allowed in comments")
             }
-            StreamError::InvalidCommentEnd => {
+            StreamError::InvalidCommentEnd(_) => {
                 write!(f, "comment cannot end with '-'")
             }
-            StreamError::InvalidCharacterData => {
+            StreamError::InvalidCharacterData(_) => {
                 write!(f, "']]>' is not allowed inside a character data")
             }
+            StreamError::UnexpectedConditionalCommentEnd(_) => {
+                write!(
+                    f,
+                    "'<![endif]>' without a matching conditional comment start"
+                )
+            }
+            StreamError::UnclosedConditionalComment(_) => {
+                write!(f, "conditional comment is not closed")
+            }
+            StreamError::ConditionalCommentTooDeep(_) => {
+                write!(f, "conditional comments are nested too deeply")
+            }
         }
     }
 }
 
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_message(f)?;
+        write!(f, " at {}", self.pos())
+    }
+}
+
 #[cfg(feature = "std")]
 impl error::Error for StreamError {
     fn description(&self) -> &str {
@@ -218,19 +336,25 @@ impl error::Error for StreamError {
 /// Position in text.
 ///
 /// Position indicates a row/line and a column in the original text. Starting from 1:1.
+/// The column is counted in [`ColumnUnit`]s, characters by default.
+///
+/// `offset` is the same position in bytes, starting from 0.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct TextPos {
     pub row: u32,
     pub col: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub offset: usize,
 }
 
 impl TextPos {
     /// Constructs a new `TextPos`.
     ///
     /// Should not be invoked manually, but rather via `Stream::gen_text_pos`.
-    pub fn new(row: u32, col: u32) -> TextPos {
-        TextPos { row, col }
+    pub fn new(row: u32, col: u32, offset: usize) -> TextPos {
+        TextPos { row, col, offset }
     }
 }
 
@@ -239,3 +363,80 @@ impl fmt::Display for TextPos {
         write!(f, "{}:{}", self.row, self.col)
     }
 }
+
+/// A unit in which [`TextPos`] columns are counted.
+///
+/// More units can be added by features, so matches must have a wildcard arm.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum ColumnUnit {
+    /// UTF-8 bytes.
+    Bytes,
+    /// Unicode scalar values, aka Rust `char`s.
+    Chars,
+    /// UTF-16 code units, like in JavaScript and the Language Server Protocol.
+    Utf16,
+    /// Extended grapheme clusters, aka user-perceived characters.
+    #[cfg(feature = "unicode-segmentation")]
+    Graphemes,
+}
+
+impl Default for ColumnUnit {
+    #[inline]
+    fn default() -> Self {
+        ColumnUnit::Chars
+    }
+}
+
+impl ColumnUnit {
+    /// Returns the length of the `text` in this unit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::ColumnUnit;
+    ///
+    /// assert_eq!(ColumnUnit::Bytes.count("т😀"), 6);
+    /// assert_eq!(ColumnUnit::Chars.count("т😀"), 2);
+    /// assert_eq!(ColumnUnit::Utf16.count("т😀"), 3);
+    /// ```
+    pub fn count(&self, text: &str) -> usize {
+        match *self {
+            ColumnUnit::Bytes => text.len(),
+            ColumnUnit::Chars => text.chars().count(),
+            ColumnUnit::Utf16 => text.encode_utf16().count(),
+            #[cfg(feature = "unicode-segmentation")]
+            ColumnUnit::Graphemes => {
+                unicode_segmentation::UnicodeSegmentation::graphemes(text, true).count()
+            }
+        }
+    }
+
+    // Returns the byte offset after `count` units of the `text`.
+    //
+    // Offsets inside a unit are moved to its end.
+    pub(crate) fn offset(&self, text: &str, count: usize) -> usize {
+        let mut n = 0;
+        for (idx, c) in text.char_indices() {
+            if n >= count {
+                return idx;
+            }
+
+            n += match *self {
+                ColumnUnit::Bytes => c.len_utf8(),
+                ColumnUnit::Chars => 1,
+                ColumnUnit::Utf16 => c.len_utf16(),
+                #[cfg(feature = "unicode-segmentation")]
+                ColumnUnit::Graphemes => {
+                    use unicode_segmentation::UnicodeSegmentation;
+                    return match text.grapheme_indices(true).nth(count) {
+                        Some((idx, _)) => idx,
+                        None => text.len(),
+                    };
+                }
+            };
+        }
+
+        text.len()
+    }
+}
@@ -0,0 +1,193 @@
+// Escaping of the text copied from the input into a generated HTML,
+// and decoding of the character references.
+//
+// Character references are kept as is when escaping, so an already escaped text
+// is not escaped twice.
+
+#[cfg(feature = "std")]
+use std::string::String;
+
+/// Returns the length of the character reference at the start of `text`,
+/// like `&amp;`, `&#38;` or `&#x26;`.
+pub(crate) fn reference_len(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if bytes.first() != Some(&b'&') {
+        return None;
+    }
+
+    let (start, is_digit): (usize, fn(&u8) -> bool) = match bytes.get(1..3) {
+        Some([b'#', b'x']) | Some([b'#', b'X']) => (3, u8::is_ascii_hexdigit),
+        Some([b'#', _]) => (2, u8::is_ascii_digit),
+        _ => {
+            if !bytes.get(1).is_some_and(u8::is_ascii_alphabetic) {
+                return None;
+            }
+            (1, u8::is_ascii_alphanumeric)
+        }
+    };
+
+    let len = bytes[start..].iter().take_while(|c| is_digit(c)).count();
+    match bytes.get(start + len) {
+        Some(b';') if len != 0 => Some(start + len + 1),
+        _ => None,
+    }
+}
+
+/// Named character references that can be decoded.
+///
+/// Only the most common ones, out of more than two thousand.
+/// They are listed in the `InnerText` documentation, which must be kept in sync.
+const ENTITIES: &[(&str, char)] = &[
+    ("AMP", '&'),
+    ("GT", '>'),
+    ("LT", '<'),
+    ("NewLine", '\n'),
+    ("QUOT", '"'),
+    ("Tab", '\t'),
+    ("amp", '&'),
+    ("apos", '\''),
+    ("bull", '\u{2022}'),
+    ("cent", '\u{A2}'),
+    ("colon", ':'),
+    ("copy", '\u{A9}'),
+    ("deg", '\u{B0}'),
+    ("divide", '\u{F7}'),
+    ("emsp", '\u{2003}'),
+    ("ensp", '\u{2002}'),
+    ("euro", '\u{20AC}'),
+    ("frac12", '\u{BD}'),
+    ("frac14", '\u{BC}'),
+    ("frac34", '\u{BE}'),
+    ("gt", '>'),
+    ("hellip", '\u{2026}'),
+    ("iexcl", '\u{A1}'),
+    ("iquest", '\u{BF}'),
+    ("laquo", '\u{AB}'),
+    ("ldquo", '\u{201C}'),
+    ("lsquo", '\u{2018}'),
+    ("lt", '<'),
+    ("mdash", '\u{2014}'),
+    ("middot", '\u{B7}'),
+    ("nbsp", '\u{A0}'),
+    ("ndash", '\u{2013}'),
+    ("para", '\u{B6}'),
+    ("plusmn", '\u{B1}'),
+    ("pound", '\u{A3}'),
+    ("quot", '"'),
+    ("raquo", '\u{BB}'),
+    ("rdquo", '\u{201D}'),
+    ("reg", '\u{AE}'),
+    ("rsquo", '\u{2019}'),
+    ("sect", '\u{A7}'),
+    ("shy", '\u{AD}'),
+    ("thinsp", '\u{2009}'),
+    ("times", '\u{D7}'),
+    ("trade", '\u{2122}'),
+    ("yen", '\u{A5}'),
+    ("zwj", '\u{200D}'),
+    ("zwnj", '\u{200C}'),
+];
+
+/// Decodes the character reference at the start of `text`,
+/// returning the character and the reference length.
+///
+/// Like browsers, numeric references don't require a semicolon
+/// and invalid code points are replaced with U+FFFD.
+/// Unknown named references are not decoded.
+pub(crate) fn decode_reference(text: &str) -> Option<(char, usize)> {
+    let (start, radix) = match text.as_bytes().get(..3)? {
+        [b'&', b'#', b'x'] | [b'&', b'#', b'X'] => (3, 16),
+        [b'&', b'#', _] => (2, 10),
+        _ => {
+            let len = reference_len(text)?;
+            let name = &text[1..len - 1];
+            let (_, c) = ENTITIES.iter().find(|(entity, _)| *entity == name)?;
+            return Some((*c, len));
+        }
+    };
+
+    let digits = text[start..]
+        .bytes()
+        .take_while(|c| (*c as char).is_digit(radix))
+        .count();
+    if digits == 0 {
+        return None;
+    }
+
+    let value = text[start..start + digits]
+        .chars()
+        .filter_map(|c| c.to_digit(radix))
+        .fold(0u32, |value, digit| {
+            value.saturating_mul(radix).saturating_add(digit)
+        });
+    let c = match value {
+        0 => '\u{FFFD}',
+        _ => core::char::from_u32(value).unwrap_or('\u{FFFD}'),
+    };
+    let len = start + digits + text[start + digits..].starts_with(';') as usize;
+    Some((c, len))
+}
+
+/// Appends the `text` with the character references decoded.
+#[cfg(feature = "std")]
+pub(crate) fn push_decoded(out: &mut String, text: &str) {
+    let mut rest = text;
+    while let Some(idx) = rest.find('&') {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        match decode_reference(rest) {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+}
+
+/// Appends the `text` with `<`, `>` and stray `&` escaped.
+///
+/// Double quotes are escaped too when `attribute` is set.
+#[cfg(feature = "std")]
+pub(crate) fn push_escaped(out: &mut String, text: &str, attribute: bool) {
+    let mut rest = text;
+    while let Some(idx) = rest.find(|c| matches!(c, '<' | '>' | '&' | '"')) {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let len = match rest.as_bytes()[0] {
+            b'<' => {
+                out.push_str("&lt;");
+                1
+            }
+            b'>' => {
+                out.push_str("&gt;");
+                1
+            }
+            b'"' if attribute => {
+                out.push_str("&quot;");
+                1
+            }
+            b'"' => {
+                out.push('"');
+                1
+            }
+            _ => match reference_len(rest) {
+                Some(len) => {
+                    out.push_str(&rest[..len]);
+                    len
+                }
+                None => {
+                    out.push_str("&amp;");
+                    1
+                }
+            },
+        };
+        rest = &rest[len..];
+    }
+    out.push_str(rest);
+}
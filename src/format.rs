@@ -0,0 +1,245 @@
+use std::string::String;
+
+use crate::tags::PREFORMATTED_TAGS;
+use crate::tree::{self, Element, End, Node};
+use crate::Error;
+
+/// An HTML formatter.
+///
+/// Block elements are placed on their own lines and indented by nesting level,
+/// while text and inline elements are kept on the same line,
+/// with the whitespace collapsed.
+/// Start tags that don't fit the maximum width have an attribute per line.
+///
+/// Tag names, attributes, text and comments are copied from the input as is,
+/// and the content of `pre`, `script`, `style` and `textarea` elements is left untouched.
+///
+/// The output is stable: formatting it again yields the same text.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::Formatter;
+///
+/// let text = "<ul><li>One</li><li>Two <b>and</b>\n three</li></ul>";
+/// assert_eq!(
+///     Formatter::new().format(text).unwrap(),
+///     "<ul>\n  <li>One</li>\n  <li>Two <b>and</b> three</li>\n</ul>\n"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Formatter {
+    indent: String,
+    max_width: usize,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Formatter {
+            indent: String::from("  "),
+            max_width: 80,
+        }
+    }
+}
+
+impl Formatter {
+    /// Creates a formatter that indents with two spaces and wraps start tags at 80 characters.
+    pub fn new() -> Self {
+        Formatter::default()
+    }
+
+    /// Sets the text of a single indentation level.
+    pub fn with_indent(mut self, indent: &str) -> Self {
+        self.indent = String::from(indent);
+        self
+    }
+
+    /// Sets the width in characters, after which the attributes of a start tag are wrapped.
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Returns the formatted `text`.
+    ///
+    /// The `text` can be a document or a fragment, like multiple paragraphs.
+    pub fn format(&self, text: &str) -> Result<String, Error> {
+        let nodes = tree::parse(text, PREFORMATTED_TAGS)?;
+
+        let mut printer = Printer {
+            options: self,
+            out: String::with_capacity(text.len()),
+        };
+        printer.block_children(&nodes, 0);
+        Ok(printer.out)
+    }
+}
+
+struct Printer<'f> {
+    options: &'f Formatter,
+    out: String,
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+impl Printer<'_> {
+    fn indent(&mut self, level: usize) {
+        push_indent(self.options, level, &mut self.out);
+    }
+
+    /// Prints the nodes on separate lines, grouping the inline ones.
+    fn block_children(&mut self, nodes: &[Node], level: usize) {
+        let mut start = 0;
+        for (idx, node) in nodes.iter().enumerate() {
+            if node.is_block() {
+                self.inline_line(&nodes[start..idx], level);
+                self.block(node, level);
+                start = idx + 1;
+            }
+        }
+        self.inline_line(&nodes[start..], level);
+    }
+
+    fn inline_line(&mut self, nodes: &[Node], level: usize) {
+        let mut line = String::new();
+        inline(self.options, nodes, level, &mut line);
+        let line = line.trim_matches(' ');
+        if !line.is_empty() {
+            self.indent(level);
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+    }
+
+    fn block(&mut self, node: &Node, level: usize) {
+        self.indent(level);
+        match node {
+            Node::Element(element) => {
+                start_tag(self.options, element, level, &mut self.out);
+                if let Some(raw) = element.raw {
+                    self.out.push_str(raw);
+                } else if element.children.iter().any(Node::is_block) {
+                    self.out.push('\n');
+                    self.block_children(&element.children, level + 1);
+                    match element.end {
+                        End::Close(_) => self.indent(level),
+                        _ => return,
+                    }
+                } else {
+                    let mut content = String::new();
+                    let wrapped = inline(self.options, &element.children, level + 1, &mut content);
+                    let content = content.trim_matches(' ');
+                    if wrapped {
+                        // Wrapped start tags are easier to read on their own lines.
+                        self.out.push('\n');
+                        self.indent(level + 1);
+                        self.out.push_str(content);
+                        self.out.push('\n');
+                        match element.end {
+                            End::Close(_) => self.indent(level),
+                            _ => return,
+                        }
+                    } else {
+                        self.out.push_str(content);
+                    }
+                }
+
+                if let End::Close(name) = element.end {
+                    push_end_tag(name, &mut self.out);
+                }
+            }
+            Node::Block(text) | Node::Inline(text) | Node::Text(text) => {
+                self.out.push_str(text.as_str())
+            }
+        }
+        self.out.push('\n');
+    }
+}
+
+fn push_indent(options: &Formatter, level: usize, out: &mut String) {
+    for _ in 0..level {
+        out.push_str(&options.indent);
+    }
+}
+
+fn push_end_tag(name: &str, out: &mut String) {
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+/// Writes the nodes on a single line, except for the wrapped start tags.
+///
+/// Returns `true` when a start tag was wrapped.
+fn inline(options: &Formatter, nodes: &[Node], level: usize, out: &mut String) -> bool {
+    let mut wrapped = false;
+    for node in nodes {
+        match node {
+            Node::Text(text) => {
+                for (idx, word) in text.as_str().split(is_space).enumerate() {
+                    if idx != 0 && !out.ends_with(' ') {
+                        out.push(' ');
+                    }
+                    out.push_str(word);
+                }
+            }
+            Node::Inline(text) | Node::Block(text) => out.push_str(text.as_str()),
+            Node::Element(element) => {
+                wrapped |= start_tag(options, element, level, out);
+                match element.raw {
+                    Some(raw) => out.push_str(raw),
+                    None => wrapped |= inline(options, &element.children, level, out),
+                }
+                if let End::Close(name) = element.end {
+                    push_end_tag(name, out);
+                }
+            }
+        }
+    }
+    wrapped
+}
+
+/// Writes the start tag, with an attribute per line when it doesn't fit the maximum width.
+///
+/// Returns `true` when the attributes were wrapped.
+fn start_tag(options: &Formatter, element: &Element, level: usize, out: &mut String) -> bool {
+    let end = match element.end {
+        End::Empty => "/>",
+        _ => ">",
+    };
+
+    let mut width =
+        options.indent.chars().count() * level + element.name.chars().count() + end.len() + 1;
+    for attr in &element.attributes {
+        width += 1 + attr.name.chars().count();
+        if let Some(value) = attr.value {
+            width += 1 + value.chars().count() + 2 * attr.quote.len();
+        }
+    }
+
+    let wrap = element.attributes.len() > 1 && width > options.max_width;
+    out.push('<');
+    out.push_str(element.name);
+    for attr in &element.attributes {
+        if wrap {
+            out.push('\n');
+            for _ in 0..level + 1 {
+                out.push_str(&options.indent);
+            }
+        } else {
+            out.push(' ');
+        }
+
+        out.push_str(attr.name);
+        if let Some(value) = attr.value {
+            out.push('=');
+            out.push_str(attr.quote);
+            out.push_str(value);
+            out.push_str(attr.quote);
+        }
+    }
+    out.push_str(end);
+    wrap
+}
@@ -0,0 +1,240 @@
+use crate::{ElementEnd, EntityDefinition, Error, ExternalId, StrSpan, Token, Tokenizer};
+
+/// A push-based alternative to the [`Tokenizer`] iterator.
+///
+/// Each method is called for the matching [`Token`] and does nothing by default,
+/// so only the interesting ones have to be implemented.
+/// Returning an error from any method stops the parsing.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{Handler, StrSpan, Tokenizer};
+///
+/// #[derive(Default)]
+/// struct Links<'a>(Vec<&'a str>);
+///
+/// impl<'a> Handler<'a> for Links<'a> {
+///     type Error = htmlparser::Error;
+///
+///     fn attribute(
+///         &mut self,
+///         _prefix: StrSpan<'a>,
+///         local: StrSpan<'a>,
+///         value: Option<StrSpan<'a>>,
+///         _span: StrSpan<'a>,
+///     ) -> Result<(), Self::Error> {
+///         if let (true, Some(value)) = (local == "href", value) {
+///             self.0.push(value.as_str());
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let mut links = Links::default();
+/// Tokenizer::from("<p><a href='/a'>a</a><a href='/b'>b</a></p>")
+///     .visit(&mut links)
+///     .unwrap();
+/// assert_eq!(links.0, vec!["/a", "/b"]);
+/// ```
+#[allow(unused_variables)]
+pub trait Handler<'a> {
+    /// The error type returned by the handler.
+    ///
+    /// Tokenizer errors are converted into it by default.
+    type Error: From<Error>;
+
+    /// Called on an XML declaration.
+    fn declaration(
+        &mut self,
+        version: StrSpan<'a>,
+        encoding: Option<StrSpan<'a>>,
+        standalone: Option<bool>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a processing instruction.
+    fn pi(
+        &mut self,
+        target: StrSpan<'a>,
+        content: Option<StrSpan<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a DOCTYPE without an internal subset, like `<!DOCTYPE html>`.
+    fn doctype(
+        &mut self,
+        name: StrSpan<'a>,
+        external_id: Option<ExternalId<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a DOCTYPE start with an internal subset, up to its `[`.
+    ///
+    /// Followed by the subset declarations and [`doctype_end`](Self::doctype_end).
+    fn doctype_start(
+        &mut self,
+        name: StrSpan<'a>,
+        external_id: Option<ExternalId<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on an ENTITY inside the DOCTYPE internal subset.
+    fn entity_declaration(
+        &mut self,
+        name: StrSpan<'a>,
+        definition: EntityDefinition<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called at the end of the DOCTYPE internal subset.
+    fn doctype_end(&mut self, span: StrSpan<'a>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a conditional comment start.
+    fn conditional_comment_start(
+        &mut self,
+        condition: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a conditional comment end.
+    fn conditional_comment_end(
+        &mut self,
+        start: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a comment.
+    fn comment(&mut self, text: StrSpan<'a>, span: StrSpan<'a>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on an element start, before its attributes.
+    fn start_element(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on an attribute of the current element start.
+    fn attribute(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        value: Option<StrSpan<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called at the end of an element start (`>` or `/>`) and on a close tag.
+    fn end_element(&mut self, end: ElementEnd<'a>, span: StrSpan<'a>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a text.
+    fn text(&mut self, text: StrSpan<'a>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a CDATA section.
+    fn cdata(&mut self, text: StrSpan<'a>, span: StrSpan<'a>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called on a tokenizer error, after which there are no more tokens.
+    ///
+    /// Returns the error by default.
+    /// Returning `Ok` ignores it and lets the parsing finish successfully.
+    fn error(&mut self, error: Error) -> Result<(), Self::Error> {
+        Err(error.into())
+    }
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Passes the remaining tokens to the `handler`.
+    ///
+    /// Stops on the first error returned by the `handler`.
+    /// The tokenizer is left right after the token that caused it,
+    /// so parsing can be resumed by calling this method again.
+    pub fn visit<H: Handler<'a>>(&mut self, handler: &mut H) -> Result<(), H::Error> {
+        for token in self {
+            let token = match token {
+                Ok(token) => token,
+                Err(e) => return handler.error(e),
+            };
+
+            match token {
+                Token::Declaration {
+                    version,
+                    encoding,
+                    standalone,
+                    span,
+                } => handler.declaration(version, encoding, standalone, span)?,
+                Token::ProcessingInstruction {
+                    target,
+                    content,
+                    span,
+                } => handler.pi(target, content, span)?,
+                Token::ConditionalCommentStart { condition, span } => {
+                    handler.conditional_comment_start(condition, span)?
+                }
+                Token::ConditionalCommentEnd { start, span } => {
+                    handler.conditional_comment_end(start, span)?
+                }
+                Token::Comment { text, span } => handler.comment(text, span)?,
+                Token::DtdStart {
+                    name,
+                    external_id,
+                    span,
+                } => handler.doctype_start(name, external_id, span)?,
+                Token::EmptyDtd {
+                    name,
+                    external_id,
+                    span,
+                } => handler.doctype(name, external_id, span)?,
+                Token::EntityDeclaration {
+                    name,
+                    definition,
+                    span,
+                } => handler.entity_declaration(name, definition, span)?,
+                Token::DtdEnd { span } => handler.doctype_end(span)?,
+                Token::ElementStart {
+                    prefix,
+                    local,
+                    span,
+                } => handler.start_element(prefix, local, span)?,
+                Token::Attribute {
+                    prefix,
+                    local,
+                    value,
+                    span,
+                } => handler.attribute(prefix, local, value, span)?,
+                Token::ElementEnd { end, span } => handler.end_element(end, span)?,
+                Token::Text { text } => handler.text(text)?,
+                Token::Cdata { text, span } => handler.cdata(text, span)?,
+            }
+        }
+
+        Ok(())
+    }
+}
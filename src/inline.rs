@@ -0,0 +1,804 @@
+use std::string::String;
+use std::vec::Vec;
+
+use crate::escape::push_escaped;
+use crate::style::{scan, skip_space};
+use crate::tree::{self, Element, Node};
+use crate::{Error, StyleDeclarations};
+
+/// Elements that are not rendered, with their content.
+const SKIPPED_TAGS: &[&str] = &["base", "head", "link", "meta", "script", "style", "title"];
+
+/// An inliner of the CSS rules of an HTML document, for email templates.
+///
+/// The rules of the `style` elements are matched against the elements
+/// and their declarations are written into the `style` attributes,
+/// following the cascade: `!important` declarations first,
+/// then the existing `style` attributes, the selector specificity and the rule order.
+///
+/// Rules that can't be inlined are left in their `style` element:
+/// at-rules like `@media` or `@font-face`, and selectors with pseudo-elements or
+/// dynamic pseudo-classes like `:hover`. The `style` elements that end up empty are removed.
+///
+/// Supported selectors are type, universal, class, ID and attribute selectors,
+/// all the combinators and the `:first-child`, `:last-child`, `:only-child`,
+/// `:nth-child()`, `:root` and `:empty` pseudo-classes.
+///
+/// Only the modified start tags and `style` elements are written, the rest of the document
+/// is copied as is. `style` elements with a `media` attribute, or inside conditional comments,
+/// are left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::CssInliner;
+///
+/// let text = "<html><head><style>p { color: red } .big { font-size: 20px }\
+///             @media (max-width: 600px) { p { color: blue } }</style></head>\
+///             <body><p class=\"big\" style=\"margin: 0\">Hi</p></body></html>";
+/// assert_eq!(
+///     CssInliner::new().inline(text).unwrap(),
+///     "<html><head><style>@media (max-width: 600px) { p { color: blue } }</style></head>\
+///      <body><p class=\"big\" style=\"color: red; font-size: 20px; margin: 0\">Hi</p></body></html>"
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct CssInliner {
+    keep_style_elements: bool,
+    preserve_important: bool,
+}
+
+impl CssInliner {
+    /// Creates an inliner that removes the inlined rules and the `!important` flags.
+    pub fn new() -> Self {
+        CssInliner::default()
+    }
+
+    /// Sets whether the `style` elements are kept as is, with all their rules.
+    pub fn with_keep_style_elements(mut self, value: bool) -> Self {
+        self.keep_style_elements = value;
+        self
+    }
+
+    /// Sets whether the inlined declarations keep their `!important` flag.
+    ///
+    /// The flag is removed by default, so the rules of the media queries
+    /// can still override the inlined declarations.
+    pub fn with_preserve_important(mut self, value: bool) -> Self {
+        self.preserve_important = value;
+        self
+    }
+
+    /// Returns the `text` with the CSS rules inlined.
+    ///
+    /// The `text` can be a document or a fragment, like multiple paragraphs.
+    pub fn inline(&self, text: &str) -> Result<String, Error> {
+        let nodes = tree::parse(text, &["script", "style"])?;
+
+        let mut inliner = Inliner {
+            options: self,
+            text,
+            rules: Vec::new(),
+            edits: Vec::new(),
+            conditionals: 0,
+        };
+        inliner.collect_rules(&nodes);
+        if !inliner.rules.is_empty() {
+            let mut path = Vec::new();
+            inliner.apply_rules(&nodes, &mut path);
+        }
+
+        let mut edits = inliner.edits;
+        edits.sort_by_key(|(start, ..)| *start);
+        let mut out = String::with_capacity(text.len());
+        let mut pos = 0;
+        for (start, end, replacement) in edits {
+            out.push_str(&text[pos..start]);
+            out.push_str(&replacement);
+            pos = end;
+        }
+        out.push_str(&text[pos..]);
+        Ok(out)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+    Adjacent,
+    Sibling,
+}
+
+enum AttributeOperator {
+    Exists,
+    Equals,
+    Includes,
+    DashMatch,
+    Prefix,
+    Suffix,
+    Substring,
+}
+
+struct AttributeSelector<'s> {
+    name: &'s str,
+    operator: AttributeOperator,
+    value: &'s str,
+    ignore_case: bool,
+}
+
+enum PseudoClass {
+    /// Matches the `a`n+`b`th child.
+    NthChild(i32, i32),
+    /// Matches the `a`n+`b`th child, counting from the last one.
+    NthLastChild(i32, i32),
+    Root,
+    Empty,
+}
+
+#[derive(Default)]
+struct Compound<'s> {
+    /// The lowercase type, or `None` for any type.
+    name: Option<String>,
+    ids: Vec<&'s str>,
+    classes: Vec<&'s str>,
+    attributes: Vec<AttributeSelector<'s>>,
+    pseudo_classes: Vec<PseudoClass>,
+}
+
+struct Selector<'s> {
+    /// The compound selectors, each with the combinator before it.
+    parts: Vec<(Combinator, Compound<'s>)>,
+    specificity: (usize, usize, usize),
+}
+
+struct Rule<'s> {
+    selectors: Vec<Selector<'s>>,
+    declarations: &'s str,
+}
+
+/// An inlined declaration.
+struct Entry<'s> {
+    property: &'s str,
+    value: &'s str,
+    important: bool,
+    /// Whether it comes from the `style` attribute.
+    inline: bool,
+    specificity: (usize, usize, usize),
+    order: usize,
+}
+
+impl Entry<'_> {
+    fn key(&self) -> String {
+        match self.property.starts_with("--") {
+            true => String::from(self.property),
+            false => self.property.to_ascii_lowercase(),
+        }
+    }
+}
+
+/// The siblings and the index of an element and all its ancestors.
+type Path<'t, 'a> = Vec<(&'t [Node<'a>], usize)>;
+
+struct Inliner<'a, 'o> {
+    options: &'o CssInliner,
+    text: &'a str,
+    rules: Vec<Rule<'a>>,
+    /// Replacements of the source ranges.
+    edits: Vec<(usize, usize, String)>,
+    /// Nesting level inside conditional comments.
+    conditionals: usize,
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()
+}
+
+/// Returns the length of the name at the start of `text`.
+fn name_len(text: &str) -> usize {
+    text.find(|c| !is_name_char(c)).unwrap_or(text.len())
+}
+
+/// Parses an `an+b` expression, like `2n+1`, `odd` or `3`.
+fn parse_nth(text: &str) -> Option<(i32, i32)> {
+    let text: String = text.chars().filter(|c| !is_space(*c)).collect();
+    let text = text.to_ascii_lowercase();
+    match text.as_str() {
+        "odd" => return Some((2, 1)),
+        "even" => return Some((2, 0)),
+        _ => {}
+    }
+
+    let parse_int = |text: &str| text.strip_prefix('+').unwrap_or(text).parse::<i32>().ok();
+    match text.find('n') {
+        Some(idx) => {
+            let a = match &text[..idx] {
+                "" | "+" => 1,
+                "-" => -1,
+                a => parse_int(a)?,
+            };
+            let b = match &text[idx + 1..] {
+                "" => 0,
+                b if b.starts_with('+') || b.starts_with('-') => parse_int(b)?,
+                _ => return None,
+            };
+            Some((a, b))
+        }
+        None => Some((0, parse_int(&text)?)),
+    }
+}
+
+/// Checks that the 1-based `index` is the `a`n+`b`th one.
+fn nth_matches(a: i32, b: i32, index: usize) -> bool {
+    let diff = index as i64 - i64::from(b);
+    match a {
+        0 => diff == 0,
+        a => diff % i64::from(a) == 0 && diff / i64::from(a) >= 0,
+    }
+}
+
+/// Returns the text of the quoted string or the name at the start of `text`,
+/// with the length in the `text`.
+fn string_or_name(text: &str) -> Option<(&str, usize)> {
+    match text.chars().next()? {
+        quote @ '"' | quote @ '\'' => {
+            let len = text[1..].find(quote)?;
+            Some((&text[1..len + 1], len + 2))
+        }
+        _ => {
+            let len = name_len(text);
+            (len != 0).then(|| (&text[..len], len))
+        }
+    }
+}
+
+/// Parses a selector, returning `None` when it's invalid or not supported.
+fn parse_selector(text: &str) -> Option<Selector<'_>> {
+    let mut parts = Vec::new();
+    let mut specificity = (0, 0, 0);
+    let mut combinator = Combinator::Descendant;
+    let mut compound: Option<Compound> = None;
+    let mut rest = text.trim_matches(is_space);
+
+    while let Some(c) = rest.chars().next() {
+        if is_space(c) || matches!(c, '>' | '+' | '~') {
+            parts.push((combinator, compound.take()?));
+            rest = rest.trim_start_matches(is_space);
+            combinator = match rest.chars().next()? {
+                '>' => Combinator::Child,
+                '+' => Combinator::Adjacent,
+                '~' => Combinator::Sibling,
+                _ => Combinator::Descendant,
+            };
+            if combinator != Combinator::Descendant {
+                rest = rest[1..].trim_start_matches(is_space);
+            }
+            continue;
+        }
+
+        let current = compound.get_or_insert_with(Compound::default);
+        match c {
+            '*' if current.name.is_none() => {
+                current.name = Some(String::from("*"));
+                rest = &rest[1..];
+            }
+            '#' | '.' => {
+                let len = name_len(&rest[1..]);
+                if len == 0 {
+                    return None;
+                }
+                let name = &rest[1..len + 1];
+                if c == '#' {
+                    current.ids.push(name);
+                    specificity.0 += 1;
+                } else {
+                    current.classes.push(name);
+                    specificity.1 += 1;
+                }
+                rest = &rest[len + 1..];
+            }
+            '[' => {
+                let end = rest.find(']')?;
+                current
+                    .attributes
+                    .push(parse_attribute_selector(&rest[1..end])?);
+                specificity.1 += 1;
+                rest = &rest[end + 1..];
+            }
+            ':' => {
+                let len = name_len(&rest[1..]);
+                let name = rest[1..len + 1].to_ascii_lowercase();
+                rest = &rest[len + 1..];
+                let argument = match rest.strip_prefix('(') {
+                    Some(text) => {
+                        let end = text.find(')')?;
+                        rest = &text[end + 1..];
+                        Some(&text[..end])
+                    }
+                    None => None,
+                };
+
+                let pseudo_class = match (name.as_str(), argument) {
+                    ("first-child", None) => PseudoClass::NthChild(0, 1),
+                    ("last-child", None) => PseudoClass::NthLastChild(0, 1),
+                    ("only-child", None) => {
+                        current.pseudo_classes.push(PseudoClass::NthChild(0, 1));
+                        PseudoClass::NthLastChild(0, 1)
+                    }
+                    ("nth-child", Some(argument)) => {
+                        let (a, b) = parse_nth(argument)?;
+                        PseudoClass::NthChild(a, b)
+                    }
+                    ("nth-last-child", Some(argument)) => {
+                        let (a, b) = parse_nth(argument)?;
+                        PseudoClass::NthLastChild(a, b)
+                    }
+                    ("root", None) => PseudoClass::Root,
+                    ("empty", None) => PseudoClass::Empty,
+                    _ => return None,
+                };
+                current.pseudo_classes.push(pseudo_class);
+                specificity.1 += 1;
+            }
+            _ if is_name_char(c) && current.name.is_none() => {
+                let len = name_len(rest);
+                current.name = Some(rest[..len].to_ascii_lowercase());
+                specificity.2 += 1;
+                rest = &rest[len..];
+            }
+            _ => return None,
+        }
+    }
+
+    parts.push((combinator, compound?));
+    Some(Selector { parts, specificity })
+}
+
+/// Parses the content of an attribute selector, like `type="text" i`.
+fn parse_attribute_selector(text: &str) -> Option<AttributeSelector<'_>> {
+    let text = text.trim_matches(is_space);
+    let len = name_len(text);
+    if len == 0 {
+        return None;
+    }
+
+    let name = &text[..len];
+    let rest = text[len..].trim_start_matches(is_space);
+    if rest.is_empty() {
+        return Some(AttributeSelector {
+            name,
+            operator: AttributeOperator::Exists,
+            value: "",
+            ignore_case: false,
+        });
+    }
+
+    let (operator, len) = match rest.as_bytes() {
+        [b'=', ..] => (AttributeOperator::Equals, 1),
+        [b'~', b'=', ..] => (AttributeOperator::Includes, 2),
+        [b'|', b'=', ..] => (AttributeOperator::DashMatch, 2),
+        [b'^', b'=', ..] => (AttributeOperator::Prefix, 2),
+        [b'$', b'=', ..] => (AttributeOperator::Suffix, 2),
+        [b'*', b'=', ..] => (AttributeOperator::Substring, 2),
+        _ => return None,
+    };
+
+    let rest = rest[len..].trim_start_matches(is_space);
+    let (value, len) = string_or_name(rest)?;
+    let ignore_case = match rest[len..].trim_matches(is_space) {
+        "" | "s" | "S" => false,
+        "i" | "I" => true,
+        _ => return None,
+    };
+
+    Some(AttributeSelector {
+        name,
+        operator,
+        value,
+        ignore_case,
+    })
+}
+
+/// Returns the element at the position in the path.
+fn element_at<'t, 'a>(path: &Path<'t, 'a>, depth: usize, idx: usize) -> &'t Element<'a> {
+    match &path[depth].0[idx] {
+        Node::Element(element) => element,
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the indices of the element siblings.
+fn element_indices<'t>(siblings: &'t [Node]) -> impl Iterator<Item = usize> + 't {
+    siblings
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| matches!(node, Node::Element(_)))
+        .map(|(idx, _)| idx)
+}
+
+fn attribute_matches(selector: &AttributeSelector, element: &Element) -> bool {
+    let value = match element.attribute(selector.name) {
+        Some(value) => value,
+        None => return false,
+    };
+
+    let (value, expected) = match selector.ignore_case {
+        true => (
+            value.to_ascii_lowercase(),
+            selector.value.to_ascii_lowercase(),
+        ),
+        false => (String::from(value), String::from(selector.value)),
+    };
+    match selector.operator {
+        AttributeOperator::Exists => true,
+        AttributeOperator::Equals => value == expected,
+        AttributeOperator::Includes => value.split(is_space).any(|item| item == expected),
+        AttributeOperator::DashMatch => {
+            value == expected || value.starts_with(&std::format!("{}-", expected))
+        }
+        AttributeOperator::Prefix => !expected.is_empty() && value.starts_with(&expected),
+        AttributeOperator::Suffix => !expected.is_empty() && value.ends_with(&expected),
+        AttributeOperator::Substring => !expected.is_empty() && value.contains(&expected),
+    }
+}
+
+fn compound_matches(compound: &Compound, path: &Path, depth: usize, idx: usize) -> bool {
+    let element = element_at(path, depth, idx);
+    if let Some(name) = &compound.name {
+        if name != "*" && *name != element.lowercase {
+            return false;
+        }
+    }
+
+    if !compound
+        .ids
+        .iter()
+        .all(|id| element.attribute("id") == Some(*id))
+    {
+        return false;
+    }
+
+    let classes = element.attribute("class").unwrap_or("");
+    if !compound
+        .classes
+        .iter()
+        .all(|class| classes.split(is_space).any(|item| item == *class))
+    {
+        return false;
+    }
+
+    if !compound
+        .attributes
+        .iter()
+        .all(|selector| attribute_matches(selector, element))
+    {
+        return false;
+    }
+
+    let siblings = path[depth].0;
+    compound
+        .pseudo_classes
+        .iter()
+        .all(|pseudo_class| match pseudo_class {
+            PseudoClass::NthChild(a, b) => {
+                let index = element_indices(siblings).take_while(|i| *i < idx).count() + 1;
+                nth_matches(*a, *b, index)
+            }
+            PseudoClass::NthLastChild(a, b) => {
+                let index = element_indices(siblings).filter(|i| *i > idx).count() + 1;
+                nth_matches(*a, *b, index)
+            }
+            PseudoClass::Root => depth == 0,
+            PseudoClass::Empty => element.children.iter().all(|child| match child {
+                Node::Element(_) => false,
+                Node::Text(text) => text.is_empty(),
+                Node::Inline(_) | Node::Block(_) => true,
+            }),
+        })
+}
+
+/// Checks that the selector parts match the element at the position, from the last one.
+fn selector_matches(
+    parts: &[(Combinator, Compound)],
+    path: &Path,
+    depth: usize,
+    idx: usize,
+) -> bool {
+    let (combinator, compound) = match parts.last() {
+        Some(part) => part,
+        None => return true,
+    };
+    if !compound_matches(compound, path, depth, idx) {
+        return false;
+    }
+
+    let parts = &parts[..parts.len() - 1];
+    if parts.is_empty() {
+        return true;
+    }
+
+    let siblings = path[depth].0;
+    match combinator {
+        Combinator::Child => {
+            depth != 0 && selector_matches(parts, path, depth - 1, path[depth - 1].1)
+        }
+        Combinator::Descendant => (0..depth)
+            .rev()
+            .any(|depth| selector_matches(parts, path, depth, path[depth].1)),
+        Combinator::Adjacent => match element_indices(siblings).take_while(|i| *i < idx).last() {
+            Some(previous) => selector_matches(parts, path, depth, previous),
+            None => false,
+        },
+        Combinator::Sibling => element_indices(siblings)
+            .take_while(|i| *i < idx)
+            .any(|previous| selector_matches(parts, path, depth, previous)),
+    }
+}
+
+/// Appends the `text` escaped for an attribute value quoted with `quote`.
+fn push_attribute_text(out: &mut String, text: &str, quote: &str) {
+    let mut escaped = String::new();
+    push_escaped(&mut escaped, text, quote == "\"");
+    if quote == "'" {
+        escaped = escaped.replace('\'', "&#39;");
+    }
+    out.push_str(&escaped);
+}
+
+impl<'a> Inliner<'a, '_> {
+    /// Updates the nesting level of conditional comments with the markup.
+    fn update_conditionals(&mut self, markup: &str) {
+        if markup.starts_with("<!--[if") || markup.starts_with("<![if") {
+            self.conditionals += 1;
+        } else if markup.starts_with("<![endif]") {
+            self.conditionals = self.conditionals.saturating_sub(1);
+        }
+    }
+
+    /// Collects the rules of the `style` elements, and replaces their content
+    /// with the rules that can't be inlined.
+    fn collect_rules(&mut self, nodes: &[Node<'a>]) {
+        for node in nodes {
+            let element = match node {
+                Node::Element(element) => element,
+                Node::Block(markup) => {
+                    self.update_conditionals(markup.as_str());
+                    continue;
+                }
+                Node::Text(_) | Node::Inline(_) => continue,
+            };
+
+            if element.lowercase != "style" {
+                self.collect_rules(&element.children);
+                continue;
+            }
+
+            let is_screen = match element.attribute("media") {
+                Some(media) => {
+                    let media = media.trim_matches(is_space);
+                    media.is_empty()
+                        || media.eq_ignore_ascii_case("all")
+                        || media.eq_ignore_ascii_case("screen")
+                }
+                None => true,
+            };
+            let css = match element.raw {
+                Some(css) if is_screen && self.conditionals == 0 => css,
+                _ => continue,
+            };
+
+            let kept = self.parse_stylesheet(css);
+            if self.options.keep_style_elements {
+                continue;
+            }
+            if kept.is_empty() {
+                self.edits
+                    .push((element.span.start, element.span.end, String::new()));
+            } else if kept != css {
+                let start = element.tag_end;
+                self.edits.push((start, start + css.len(), kept));
+            }
+        }
+    }
+
+    /// Collects the rules of the stylesheet, returning the ones that can't be inlined.
+    fn parse_stylesheet(&mut self, css: &'a str) -> String {
+        let bytes = css.as_bytes();
+        let mut kept: Vec<String> = Vec::new();
+        let mut pos = 0;
+        loop {
+            pos = skip_space(css, pos, b"");
+            if css[pos..].starts_with("<!--") {
+                pos += 4;
+                continue;
+            }
+            if css[pos..].starts_with("-->") {
+                pos += 3;
+                continue;
+            }
+            if pos >= css.len() {
+                break;
+            }
+
+            if bytes[pos] == b'@' {
+                let mut end = scan(css, pos, b";{");
+                if bytes.get(end) == Some(&b'{') {
+                    end = scan(css, end + 1, b"}");
+                }
+                end = (end + 1).min(css.len());
+                kept.push(String::from(css[pos..end].trim_matches(is_space)));
+                pos = end;
+                continue;
+            }
+
+            let open = scan(css, pos, b"{");
+            if open == css.len() {
+                break;
+            }
+            let close = scan(css, open + 1, b"}");
+            let prelude = &css[pos..open];
+            let declarations = &css[open + 1..close];
+            pos = (close + 1).min(css.len());
+
+            let mut selectors = Vec::new();
+            let mut unsupported = Vec::new();
+            let mut start = 0;
+            while start <= prelude.len() {
+                let end = scan(prelude, start, b",");
+                let text = prelude[start..end].trim_matches(is_space);
+                match parse_selector(text) {
+                    Some(selector) => selectors.push(selector),
+                    None if !text.is_empty() => unsupported.push(text),
+                    None => {}
+                }
+                start = end + 1;
+            }
+
+            if !unsupported.is_empty() {
+                kept.push(std::format!(
+                    "{} {{{}}}",
+                    unsupported.join(", "),
+                    declarations
+                ));
+            }
+            if !selectors.is_empty() {
+                self.rules.push(Rule {
+                    selectors,
+                    declarations,
+                });
+            }
+        }
+
+        kept.join("\n")
+    }
+
+    /// Inlines the rules into the elements.
+    fn apply_rules<'t>(&mut self, nodes: &'t [Node<'a>], path: &mut Path<'t, 'a>) {
+        for (idx, node) in nodes.iter().enumerate() {
+            if let Node::Element(element) = node {
+                if SKIPPED_TAGS.contains(&element.lowercase.as_str()) {
+                    continue;
+                }
+
+                path.push((nodes, idx));
+                self.inline_element(element, path);
+                self.apply_rules(&element.children, path);
+                path.pop();
+            }
+        }
+    }
+
+    fn inline_element(&mut self, element: &Element<'a>, path: &Path) {
+        let depth = path.len() - 1;
+        let idx = path[depth].1;
+
+        let mut entries = Vec::new();
+        let mut order = 0;
+        for rule in &self.rules {
+            let specificity = rule
+                .selectors
+                .iter()
+                .filter(|selector| selector_matches(&selector.parts, path, depth, idx))
+                .map(|selector| selector.specificity)
+                .max();
+
+            for declaration in StyleDeclarations::from(rule.declarations) {
+                order += 1;
+                if let Some(specificity) = specificity {
+                    entries.push(Entry {
+                        property: declaration.property().as_str(),
+                        value: declaration.value().as_str(),
+                        important: declaration.is_important(),
+                        inline: false,
+                        specificity,
+                        order,
+                    });
+                }
+            }
+        }
+        if entries.is_empty() {
+            return;
+        }
+
+        let attribute = element
+            .attributes
+            .iter()
+            .find(|attr| attr.name.eq_ignore_ascii_case("style"));
+        let style = attribute.and_then(|attr| attr.value).unwrap_or("");
+        for declaration in StyleDeclarations::from(style) {
+            order += 1;
+            entries.push(Entry {
+                property: declaration.property().as_str(),
+                value: declaration.value().as_str(),
+                important: declaration.is_important(),
+                inline: true,
+                specificity: (0, 0, 0),
+                order,
+            });
+        }
+
+        // Keep the winning declaration of each property, in the cascade order,
+        // so the shorthand and longhand properties still override each other.
+        entries.sort_by_key(|entry| {
+            (
+                entry.important,
+                entry.inline,
+                entry.specificity,
+                entry.order,
+            )
+        });
+        let keys: Vec<String> = entries.iter().map(Entry::key).collect();
+        let quote = match attribute {
+            Some(attr) if attr.value.is_some() => attr.quote,
+            _ => "\"",
+        };
+
+        let mut value = String::new();
+        for (idx, entry) in entries.iter().enumerate() {
+            if keys[idx + 1..].contains(&keys[idx]) {
+                continue;
+            }
+
+            if !value.is_empty() {
+                value.push_str("; ");
+            }
+            let mut text = String::from(entry.property);
+            text.push_str(": ");
+            text.push_str(entry.value);
+            if entry.important && self.options.preserve_important {
+                text.push_str(" !important");
+            }
+            match entry.inline {
+                // Already escaped in the source.
+                true => value.push_str(&text),
+                false => push_attribute_text(&mut value, &text, quote),
+            }
+        }
+
+        match attribute {
+            Some(attr) => {
+                let mut text = String::from(attr.name);
+                text.push('=');
+                text.push_str(quote);
+                text.push_str(&value);
+                text.push_str(quote);
+                self.edits.push((attr.span.start(), attr.span.end(), text));
+            }
+            None => {
+                let mut pos = element.tag_end - 1;
+                if self.text[..pos].ends_with('/') {
+                    pos -= 1;
+                }
+                let text = match self.text[..pos].ends_with(is_space) {
+                    true => std::format!("style=\"{}\" ", value),
+                    false => std::format!(" style=\"{}\"", value),
+                };
+                self.edits.push((pos, pos, text));
+            }
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use core::fmt;
+use core::ops::Range;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::escape::decode_reference;
+use crate::tags::{lowercase_name, BLOCK_TAGS, VOID_TAGS};
+use crate::{ElementEnd, Error, Handler, StrSpan, Tokenizer};
+
+/// Elements that are not rendered.
+const HIDDEN_TAGS: &[&str] = &["head", "script", "style", "template", "title"];
+
+/// Elements whose whitespace is preserved.
+const PRE_TAGS: &[&str] = &["pre", "textarea"];
+
+/// A part of the text that comes from a single source range.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Segment {
+    start: usize,
+    source: Range<usize>,
+}
+
+/// A readable text of an HTML document, like the `innerText` of its root element.
+///
+/// - `script`, `style`, `head`, `template` and `title` elements are skipped,
+///   as well as comments, conditional comments with their content, CDATA
+///   and processing instructions.
+/// - Whitespace is collapsed, except inside `pre` and `textarea` elements.
+/// - Block elements are separated by a line break, paragraphs by an empty line
+///   and table cells by a tab.
+/// - `br` elements are replaced with a line break.
+/// - Character references are decoded, see below.
+///
+/// Each part of the text can be mapped back to the source.
+///
+/// # Character references
+///
+/// Numeric references, like `&#38;` or `&#x26;`, are always decoded,
+/// even without the trailing semicolon. Invalid code points are replaced with U+FFFD.
+///
+/// Only the following named references are decoded, with the semicolon:
+/// `&amp;`, `&lt;`, `&gt;`, `&quot;`, `&apos;` (and `&AMP;`, `&LT;`, `&GT;`, `&QUOT;`),
+/// `&Tab;`, `&NewLine;`, `&nbsp;`, `&ensp;`, `&emsp;`, `&thinsp;`, `&shy;`, `&zwj;`,
+/// `&zwnj;`, `&colon;`, `&bull;`, `&middot;`, `&hellip;`, `&ndash;`, `&mdash;`,
+/// `&lsquo;`, `&rsquo;`, `&ldquo;`, `&rdquo;`, `&laquo;`, `&raquo;`, `&iexcl;`,
+/// `&iquest;`, `&cent;`, `&pound;`, `&euro;`, `&yen;`, `&copy;`, `&reg;`, `&trade;`,
+/// `&deg;`, `&plusmn;`, `&times;`, `&divide;`, `&frac14;`, `&frac12;`, `&frac34;`,
+/// `&para;` and `&sect;`. Other named references are kept as is.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::InnerText;
+///
+/// let html = "<div><h1>Fish &amp; chips</h1><p>Best   in <b>town</b>!</p></div>";
+/// let text = InnerText::new(html).unwrap();
+/// assert_eq!(text.as_str(), "Fish & chips\n\nBest in town!");
+///
+/// let idx = text.as_str().find("town").unwrap();
+/// let span = text.source_span(idx..idx + 4);
+/// assert_eq!(span.as_str(), "town");
+/// assert_eq!(span.start(), 46);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct InnerText<'a> {
+    source: &'a str,
+    text: String,
+    segments: Vec<Segment>,
+}
+
+impl<'a> InnerText<'a> {
+    /// Extracts the text from the `source` HTML.
+    ///
+    /// The `source` can be a document or a fragment, like multiple paragraphs.
+    pub fn new(source: &'a str) -> Result<Self, Error> {
+        let mut handler = Extract {
+            text: InnerText {
+                source,
+                text: String::new(),
+                segments: Vec::new(),
+            },
+            tag: None,
+            hidden: Vec::new(),
+            conditionals: 0,
+            pre: 0,
+            pre_start: false,
+            space: None,
+            breaks: 0,
+            breaks_source: 0..0,
+            cell: false,
+        };
+
+        Tokenizer::from(source)
+            .with_fragment_parsing()
+            .visit(&mut handler)?;
+        Ok(handler.text)
+    }
+
+    /// Returns the extracted text.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the source HTML.
+    #[inline]
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the source byte offset that produced the text at the `offset`.
+    ///
+    /// A character that doesn't appear in the source as is,
+    /// like a decoded character reference or a collapsed whitespace,
+    /// is mapped to the start of the markup that produced it.
+    pub fn source_offset(&self, offset: usize) -> usize {
+        match self.segment_at(offset.min(self.text.len())) {
+            Some((segment, len)) => {
+                let offset = offset.min(segment.start + len) - segment.start;
+                if len == segment.source.len() {
+                    segment.source.start + offset
+                } else {
+                    segment.source.start
+                }
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns the source span that produced the text in the `range`.
+    ///
+    /// The span covers the whole markup of the characters
+    /// that don't appear in the source as is.
+    pub fn source_span(&self, range: Range<usize>) -> StrSpan<'a> {
+        let start = self.source_offset(range.start);
+        let end = match range.end.min(self.text.len()) {
+            end if end > range.start => match self.segment_at(end - 1) {
+                Some((segment, len)) if len == segment.source.len() => {
+                    segment.source.start + end - segment.start
+                }
+                Some((segment, _)) => segment.source.end,
+                None => start,
+            },
+            _ => start,
+        };
+
+        StrSpan::from_substr(self.source, start, end.max(start))
+    }
+
+    /// Returns the segment that contains the `offset` along with its length in the text.
+    fn segment_at(&self, offset: usize) -> Option<(&Segment, usize)> {
+        let idx = self
+            .segments
+            .partition_point(|segment| segment.start <= offset)
+            .checked_sub(1)?;
+        let segment = &self.segments[idx];
+        let end = match self.segments.get(idx + 1) {
+            Some(next) => next.start,
+            None => self.text.len(),
+        };
+        Some((segment, end - segment.start))
+    }
+
+    fn push(&mut self, text: &str, source: Range<usize>) {
+        let start = self.text.len();
+        self.text.push_str(text);
+
+        // Merge with the previous segment when both are copied as is.
+        if let Some(last) = self.segments.last_mut() {
+            let is_copy = start - last.start == last.source.len();
+            if is_copy && text.len() == source.len() && last.source.end == source.start {
+                last.source.end = source.end;
+                return;
+            }
+        }
+
+        self.segments.push(Segment { start, source });
+    }
+}
+
+impl fmt::Display for InnerText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+fn is_space(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')
+}
+
+struct Extract<'a> {
+    text: InnerText<'a>,
+    /// The lowercase name and the start of the current element start.
+    tag: Option<(String, usize)>,
+    /// Names of the hidden elements that are not closed yet.
+    hidden: Vec<String>,
+    /// Nesting level inside conditional comments.
+    conditionals: usize,
+    /// Nesting level inside preformatted elements.
+    pre: usize,
+    /// Whether a preformatted element has just started.
+    pre_start: bool,
+    /// The source of a pending collapsed whitespace.
+    space: Option<Range<usize>>,
+    /// The number of pending line breaks.
+    breaks: usize,
+    breaks_source: Range<usize>,
+    /// Whether a table cell was seen in the current row.
+    cell: bool,
+}
+
+impl Extract<'_> {
+    fn is_hidden(&self) -> bool {
+        !self.hidden.is_empty() || self.conditionals != 0
+    }
+
+    fn add_breaks(&mut self, count: usize, source: Range<usize>) {
+        self.space = None;
+        if count > self.breaks {
+            self.breaks = count;
+            self.breaks_source = source;
+        }
+    }
+
+    /// Appends the `text`, after the pending line breaks or whitespace.
+    fn push(&mut self, text: &str, source: Range<usize>) {
+        if !self.text.text.is_empty() {
+            if self.breaks != 0 {
+                for _ in 0..self.breaks {
+                    self.text.push("\n", self.breaks_source.clone());
+                }
+            } else if let Some(space) = self.space.take() {
+                if !self.text.text.ends_with('\n') {
+                    self.text.push(" ", space);
+                }
+            }
+        }
+
+        self.breaks = 0;
+        self.space = None;
+        self.text.push(text, source);
+    }
+
+    fn push_text(&mut self, text: &str, start: usize) {
+        let bytes = text.as_bytes();
+        let collapse = self.pre == 0;
+        let mut idx = 0;
+        while idx < text.len() {
+            if collapse && is_space(bytes[idx]) {
+                let len = bytes[idx..].iter().take_while(|c| is_space(**c)).count();
+                if self.space.is_none() {
+                    self.space = Some(start + idx..start + idx + len);
+                }
+                idx += len;
+                continue;
+            }
+
+            if bytes[idx] == b'&' {
+                if let Some((c, len)) = decode_reference(&text[idx..]) {
+                    let mut buf = [0; 4];
+                    self.push(c.encode_utf8(&mut buf), start + idx..start + idx + len);
+                    idx += len;
+                    continue;
+                }
+            }
+
+            let len = bytes[idx + 1..]
+                .iter()
+                .position(|c| *c == b'&' || (collapse && is_space(*c)))
+                .map_or(text.len() - idx, |len| len + 1);
+            self.push(&text[idx..idx + len], start + idx..start + idx + len);
+            idx += len;
+        }
+    }
+}
+
+impl<'a> Handler<'a> for Extract<'a> {
+    type Error = Error;
+
+    fn conditional_comment_start(
+        &mut self,
+        _condition: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.conditionals += 1;
+        Ok(())
+    }
+
+    fn conditional_comment_end(
+        &mut self,
+        _start: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.conditionals = self.conditionals.saturating_sub(1);
+        Ok(())
+    }
+
+    fn start_element(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        let name = lowercase_name(prefix, local);
+        if !self.is_hidden() {
+            match name.as_str() {
+                "tr" => {
+                    self.add_breaks(1, span.range());
+                    self.cell = false;
+                }
+                "td" | "th" => {
+                    if self.cell {
+                        self.push("\t", span.range());
+                    }
+                    self.cell = true;
+                }
+                "p" => self.add_breaks(2, span.range()),
+                _ if BLOCK_TAGS.contains(&name.as_str()) => self.add_breaks(1, span.range()),
+                _ => {}
+            }
+        }
+
+        self.pre_start = false;
+        self.tag = Some((name, span.start()));
+        Ok(())
+    }
+
+    fn end_element(&mut self, end: ElementEnd<'a>, span: StrSpan<'a>) -> Result<(), Error> {
+        let (name, start) = match end {
+            ElementEnd::Open | ElementEnd::Empty => self.tag.take().unwrap_or_default(),
+            ElementEnd::Close(prefix, local) => (lowercase_name(prefix, local), span.start()),
+        };
+
+        // Browsers treat a stray `</br>` like `<br>`.
+        if name == "br" {
+            if !self.is_hidden() {
+                self.space = None;
+                self.push("\n", start..span.end());
+            }
+            return Ok(());
+        }
+
+        match end {
+            ElementEnd::Open if VOID_TAGS.contains(&name.as_str()) => {}
+            ElementEnd::Open => {
+                if !self.hidden.is_empty() || HIDDEN_TAGS.contains(&name.as_str()) {
+                    self.hidden.push(name);
+                } else if PRE_TAGS.contains(&name.as_str()) {
+                    self.pre += 1;
+                    self.pre_start = true;
+                }
+            }
+            ElementEnd::Empty => {}
+            ElementEnd::Close(..) => {
+                if !self.hidden.is_empty() {
+                    // Stray end tags inside a hidden element must not end it.
+                    if let Some(idx) = self.hidden.iter().rposition(|open| *open == name) {
+                        self.hidden.truncate(idx);
+                    }
+                    return Ok(());
+                }
+
+                if PRE_TAGS.contains(&name.as_str()) {
+                    self.pre = self.pre.saturating_sub(1);
+                }
+
+                if !self.is_hidden() {
+                    match name.as_str() {
+                        "p" => self.add_breaks(2, span.range()),
+                        "td" | "th" => {}
+                        _ if BLOCK_TAGS.contains(&name.as_str()) => {
+                            self.add_breaks(1, span.range())
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn text(&mut self, text: StrSpan<'a>) -> Result<(), Error> {
+        if self.is_hidden() {
+            return Ok(());
+        }
+
+        let mut source = text.as_str();
+        let mut start = text.start();
+        if core::mem::replace(&mut self.pre_start, false) && source.starts_with('\n') {
+            // A newline right after the start tag is ignored.
+            source = &source[1..];
+            start += 1;
+        }
+
+        self.push_text(source, start);
+        Ok(())
+    }
+}
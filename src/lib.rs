@@ -33,10 +33,13 @@
 //!   substring in the original document.
 //! - Good error processing. All error types contain the position (line:column)
 //!   where it occurred.
-//! - No heap allocations.
-//! - No dependencies.
-//! - Tiny. ~1400 LOC and ~30KiB in the release build according to
-//!   `cargo-bloat`.
+//! - No heap allocations in the tokenizer. The tools built on top of it, like the
+//!   formatter, the sanitizer or the CSS inliner, allocate and require `std`.
+//! - No dependencies by default. `serde` support is available behind the `serde`
+//!   feature, grapheme columns behind the `unicode-segmentation` feature and
+//!   SIMD-accelerated scanning behind the `memchr` feature.
+//! - A tiny tokenizer. ~1400 LOC and ~30KiB in the release build according to
+//!   `cargo-bloat`, without the `std` only tools.
 //! - Supports `no_std` builds. To use without the standard library, disable the
 //!   default features.
 //!
@@ -103,19 +106,83 @@ macro_rules! matches {
     }
 }
 
+mod condition;
+mod diagnostic;
+mod element;
 mod error;
+mod escape;
+#[cfg(feature = "std")]
+mod format;
+mod handler;
+#[cfg(feature = "std")]
+mod inline;
+#[cfg(feature = "std")]
+mod innertext;
+#[cfg(feature = "std")]
+mod lineindex;
+#[cfg(feature = "std")]
+mod links;
+#[cfg(feature = "lsp")]
+mod lsp;
+#[cfg(feature = "std")]
+mod markdown;
+#[cfg(feature = "std")]
+mod minify;
+mod mso;
+#[cfg(feature = "std")]
+mod owned;
+#[cfg(feature = "std")]
+mod sanitize;
+mod scan;
+mod srcset;
 mod stream;
 mod strspan;
+mod style;
+mod tags;
+#[cfg(feature = "std")]
+mod tree;
+#[cfg(feature = "std")]
+mod url;
 mod xmlchar;
 
+pub use crate::condition::*;
+pub use crate::diagnostic::*;
+pub use crate::element::*;
 pub use crate::error::*;
+#[cfg(feature = "std")]
+pub use crate::format::*;
+pub use crate::handler::*;
+#[cfg(feature = "std")]
+pub use crate::inline::*;
+#[cfg(feature = "std")]
+pub use crate::innertext::*;
+#[cfg(feature = "std")]
+pub use crate::lineindex::*;
+#[cfg(feature = "std")]
+pub use crate::links::*;
+#[cfg(feature = "lsp")]
+pub use crate::lsp::*;
+#[cfg(feature = "std")]
+pub use crate::markdown::*;
+#[cfg(feature = "std")]
+pub use crate::minify::*;
+pub use crate::mso::*;
+#[cfg(feature = "std")]
+pub use crate::owned::*;
+#[cfg(feature = "std")]
+pub use crate::sanitize::*;
+pub use crate::srcset::*;
 pub use crate::stream::*;
 pub use crate::strspan::*;
+pub use crate::style::*;
+#[cfg(feature = "std")]
+pub use crate::url::*;
 pub use crate::xmlchar::*;
 
 /// An XML token.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Token<'a> {
     /// Declaration token.
     ///
@@ -191,7 +258,21 @@ pub enum Token<'a> {
     /// <!--<![endif]-->
     /// ---------------- - span
     /// ```
-    ConditionalCommentEnd { span: StrSpan<'a> },
+    ///
+    /// Conditional comments can be nested, so `start` contains the span
+    /// of the matching `ConditionalCommentStart` token.
+    ///
+    /// ```text
+    /// <!--[if mso]><![if gt mso 11]>...<![endif]><![endif]-->
+    ///              -----------------                          - inner start
+    ///                                  ----------             - inner span
+    /// -------------                                           - outer start
+    ///                                            ------------ - outer span
+    /// ```
+    ConditionalCommentEnd {
+        start: StrSpan<'a>,
+        span: StrSpan<'a>,
+    },
 
     /// Comment token.
     ///
@@ -347,7 +428,7 @@ impl<'a> Token<'a> {
             Token::Declaration { span, .. } => span,
             Token::ProcessingInstruction { span, .. } => span,
             Token::ConditionalCommentStart { span, .. } => span,
-            Token::ConditionalCommentEnd { span } => span,
+            Token::ConditionalCommentEnd { span, .. } => span,
             Token::Comment { span, .. } => span,
             Token::DtdStart { span, .. } => span,
             Token::EmptyDtd { span, .. } => span,
@@ -365,6 +446,7 @@ impl<'a> Token<'a> {
 
 /// `ElementEnd` token.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ElementEnd<'a> {
     /// Indicates `>`
     Open,
@@ -377,6 +459,7 @@ pub enum ElementEnd<'a> {
 /// Representation of the [ExternalID](https://www.w3.org/TR/xml/#NT-ExternalID) value.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ExternalId<'a> {
     System(StrSpan<'a>),
     Public(StrSpan<'a>, StrSpan<'a>),
@@ -385,6 +468,7 @@ pub enum ExternalId<'a> {
 /// Representation of the [EntityDef](https://www.w3.org/TR/xml/#NT-EntityDef) value.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum EntityDefinition<'a> {
     EntityValue(StrSpan<'a>),
     ExternalId(ExternalId<'a>),
@@ -393,6 +477,53 @@ pub enum EntityDefinition<'a> {
 type Result<T> = core::result::Result<T, Error>;
 type StreamResult<T> = core::result::Result<T, StreamError>;
 
+/// The maximum nesting level of conditional comments.
+const MAX_CONDITIONAL_DEPTH: usize = 8;
+
+/// Spans of the conditional comment starts that are not closed yet.
+#[derive(Clone)]
+struct ConditionalStack<'a> {
+    starts: [StrSpan<'a>; MAX_CONDITIONAL_DEPTH],
+    depth: usize,
+}
+
+impl<'a> ConditionalStack<'a> {
+    fn new() -> Self {
+        ConditionalStack {
+            starts: [StrSpan::from(""); MAX_CONDITIONAL_DEPTH],
+            depth: 0,
+        }
+    }
+
+    // Returns `false` when the nesting level is too deep.
+    fn push(&mut self, span: StrSpan<'a>) -> bool {
+        if self.depth == MAX_CONDITIONAL_DEPTH {
+            return false;
+        }
+
+        self.starts[self.depth] = span;
+        self.depth += 1;
+        true
+    }
+
+    fn pop(&mut self) -> Option<StrSpan<'a>> {
+        if self.depth == 0 {
+            return None;
+        }
+
+        self.depth -= 1;
+        Some(self.starts[self.depth])
+    }
+
+    fn last(&self) -> Option<StrSpan<'a>> {
+        if self.depth == 0 {
+            None
+        } else {
+            Some(self.starts[self.depth - 1])
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum State {
     Declaration,
@@ -411,6 +542,7 @@ pub struct Tokenizer<'a> {
     stream: Stream<'a>,
     state: State,
     depth: usize,
+    conditionals: ConditionalStack<'a>,
     fragment_parsing: bool,
 }
 
@@ -434,6 +566,7 @@ impl<'a> From<&'a str> for Tokenizer<'a> {
             stream,
             state: State::Declaration,
             depth: 0,
+            conditionals: ConditionalStack::new(),
             fragment_parsing: false,
         }
     }
@@ -474,10 +607,80 @@ impl<'a> Tokenizer<'a> {
             stream: Stream::from_substr(full_text, fragment),
             state: State::Elements,
             depth: 0,
+            conditionals: ConditionalStack::new(),
             fragment_parsing: true,
         }
     }
 
+    /// Enables document fragment parsing after the prolog.
+    ///
+    /// Unlike [`Tokenizer::from_fragment`], the XML declaration and DOCTYPE are still parsed,
+    /// but any content can follow them, like multiple root elements or text.
+    #[cfg(feature = "std")]
+    pub(crate) fn with_fragment_parsing(mut self) -> Self {
+        self.fragment_parsing = true;
+        self
+    }
+
+    /// Resumes after an error, `len` bytes after the start of the token that failed to parse.
+    ///
+    /// Keeps the conditional comments and the position settings,
+    /// but allows any content from then on, since the structure around the error is unknown.
+    pub(crate) fn resume(&mut self, len: usize) {
+        self.stream.advance(len);
+        self.state = match self.state {
+            State::Declaration => State::AfterDeclaration,
+            State::Attributes | State::AfterElements => State::Elements,
+            state => state,
+        };
+        self.fragment_parsing = true;
+    }
+
+    /// Sets the unit used to count columns in error positions.
+    ///
+    /// Columns are counted in characters by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::{ColumnUnit, Tokenizer};
+    ///
+    /// let text = "<p>😀<!x></p>";
+    /// let error = Tokenizer::from(text)
+    ///     .with_column_unit(ColumnUnit::Utf16)
+    ///     .find_map(|t| t.err())
+    ///     .unwrap();
+    /// assert_eq!(error.pos().col, 6);
+    /// ```
+    pub fn with_column_unit(mut self, unit: ColumnUnit) -> Self {
+        self.stream.set_column_unit(unit);
+        self
+    }
+
+    /// Sets the index used to compute error positions, instead of rescanning the text.
+    ///
+    /// Useful when positions are needed for many errors or tokens of the same text,
+    /// like with [`Errors`]. The index must be built from the tokenized text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::{LineIndex, Tokenizer};
+    ///
+    /// let text = "<p>\n<!x></p>";
+    /// let index = LineIndex::new(text);
+    /// let error = Tokenizer::from(text)
+    ///     .with_line_index(&index)
+    ///     .find_map(|t| t.err())
+    ///     .unwrap();
+    /// assert_eq!(error.to_string(), "unknown token at 2:1");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_line_index(mut self, index: &'a LineIndex<'a>) -> Self {
+        self.stream.set_line_index(index);
+        self
+    }
+
     fn parse_next_impl(&mut self) -> Option<Result<Token<'a>>> {
         let s = &mut self.stream;
 
@@ -507,7 +710,7 @@ impl<'a> Tokenizer<'a> {
 
                     Some(t)
                 } else if is_conditional_comment(s) {
-                    Some(Self::parse_conditional_comment(s))
+                    Some(Self::parse_conditional_comment(s, &mut self.conditionals))
                 } else if s.starts_with(b"<!--") {
                     Some(Self::parse_comment(s))
                 } else if s.starts_with(b"<?") {
@@ -551,10 +754,7 @@ impl<'a> Tokenizer<'a> {
                             let e = StreamError::InvalidChar(c, b'>', s.gen_text_pos());
                             Some(Err(Error::InvalidDoctype(e, s.gen_text_pos_from(start))))
                         }
-                        Err(_) => {
-                            let e = StreamError::UnexpectedEndOfStream;
-                            Some(Err(Error::InvalidDoctype(e, s.gen_text_pos_from(start))))
-                        }
+                        Err(e) => Some(Err(Error::InvalidDoctype(e, s.gen_text_pos_from(start)))),
                     }
                 } else if s.starts_with_space() {
                     s.skip_spaces();
@@ -575,7 +775,7 @@ impl<'a> Tokenizer<'a> {
             }
             State::AfterDtd => {
                 if is_conditional_comment(s) {
-                    Some(Self::parse_conditional_comment(s))
+                    Some(Self::parse_conditional_comment(s, &mut self.conditionals))
                 } else if s.starts_with(b"<!--") {
                     Some(Self::parse_comment(s))
                 } else if s.starts_with(b"<?") {
@@ -592,6 +792,9 @@ impl<'a> Tokenizer<'a> {
                 } else if s.starts_with_space() {
                     s.skip_spaces();
                     None
+                } else if self.fragment_parsing {
+                    self.state = State::Elements;
+                    None
                 } else {
                     Some(Err(Error::UnknownToken(s.gen_text_pos())))
                 }
@@ -602,7 +805,7 @@ impl<'a> Tokenizer<'a> {
                     Ok(b'<') => match s.next_byte() {
                         Ok(b'!') => {
                             if is_conditional_comment(s) {
-                                Some(Self::parse_conditional_comment(s))
+                                Some(Self::parse_conditional_comment(s, &mut self.conditionals))
                             } else if s.starts_with(b"<!--") {
                                 Some(Self::parse_comment(s))
                             } else if s.starts_with(b"<![CDATA[") {
@@ -660,7 +863,7 @@ impl<'a> Tokenizer<'a> {
             }
             State::AfterElements => {
                 if is_conditional_comment(s) {
-                    Some(Self::parse_conditional_comment(s))
+                    Some(Self::parse_conditional_comment(s, &mut self.conditionals))
                 } else if s.starts_with(b"<!--") {
                     Some(Self::parse_comment(s))
                 } else if s.starts_with(b"<?") {
@@ -790,13 +993,19 @@ impl<'a> Tokenizer<'a> {
         Ok(Some(flag))
     }
 
-    fn parse_conditional_comment(s: &mut Stream<'a>) -> Result<Token<'a>> {
+    fn parse_conditional_comment(
+        s: &mut Stream<'a>,
+        conditionals: &mut ConditionalStack<'a>,
+    ) -> Result<Token<'a>> {
         let start = s.pos();
-        Self::parse_conditional_comment_impl(s)
+        Self::parse_conditional_comment_impl(s, conditionals)
             .map_err(|e| Error::InvalidConditionalComment(e, s.gen_text_pos_from(start)))
     }
 
-    fn parse_conditional_comment_impl(s: &mut Stream<'a>) -> StreamResult<Token<'a>> {
+    fn parse_conditional_comment_impl(
+        s: &mut Stream<'a>,
+        conditionals: &mut ConditionalStack<'a>,
+    ) -> StreamResult<Token<'a>> {
         let start = s.pos();
         // Downlevel-hidden conditional comment
         let condition = if s.starts_with(b"<!--[if") {
@@ -845,9 +1054,20 @@ impl<'a> Tokenizer<'a> {
         let span = s.slice_back(start);
 
         if let Some(condition) = condition {
+            if !conditionals.push(span) {
+                let pos = s.gen_text_pos_from(start);
+                return Err(StreamError::ConditionalCommentTooDeep(pos));
+            }
+
             Ok(Token::ConditionalCommentStart { condition, span })
         } else {
-            Ok(Token::ConditionalCommentEnd { span })
+            match conditionals.pop() {
+                Some(start) => Ok(Token::ConditionalCommentEnd { start, span }),
+                None => {
+                    let pos = s.gen_text_pos_from(start);
+                    Err(StreamError::UnexpectedConditionalCommentEnd(pos))
+                }
+            }
         }
     }
 
@@ -861,15 +1081,18 @@ impl<'a> Tokenizer<'a> {
     fn parse_comment_impl(s: &mut Stream<'a>) -> StreamResult<Token<'a>> {
         let start = s.pos();
         s.advance(4);
-        let text = s.consume_chars(|s, c| !(c == '-' && s.starts_with(b"-->")))?;
+        let text_start = s.pos();
+        let text = s.consume_until(b"-->")?;
         s.skip_string(b"-->")?;
 
-        if text.as_str().contains("--") {
-            return Err(StreamError::InvalidCommentData);
+        if let Some(idx) = text.as_str().find("--") {
+            let pos = s.gen_text_pos_from(text_start + idx);
+            return Err(StreamError::InvalidCommentData(pos));
         }
 
         if text.as_str().ends_with('-') {
-            return Err(StreamError::InvalidCommentEnd);
+            let pos = s.gen_text_pos_from(text_start + text.as_str().len() - 1);
+            return Err(StreamError::InvalidCommentEnd(pos));
         }
 
         let span = s.slice_back(start);
@@ -888,7 +1111,7 @@ impl<'a> Tokenizer<'a> {
         s.advance(2);
         let target = s.consume_name()?;
         s.skip_spaces();
-        let content = s.consume_chars(|s, c| !(c == '?' && s.starts_with(b"?>")))?;
+        let content = s.consume_until(b"?>")?;
         let content = if !content.is_empty() {
             Some(content)
         } else {
@@ -1046,7 +1269,7 @@ impl<'a> Tokenizer<'a> {
 
                     Ok(EntityDefinition::ExternalId(id))
                 } else {
-                    Err(StreamError::InvalidExternalID)
+                    Err(StreamError::InvalidExternalID(s.gen_text_pos()))
                 }
             }
             _ => {
@@ -1074,7 +1297,7 @@ impl<'a> Tokenizer<'a> {
     fn parse_cdata_impl(s: &mut Stream<'a>) -> StreamResult<Token<'a>> {
         let start = s.pos();
         s.advance(9);
-        let text = s.consume_chars(|s, c| !(c == ']' && s.starts_with(b"]]>")))?;
+        let text = s.consume_until(b"]]>")?;
         s.skip_string(b"]]>")?;
         let span = s.slice_back(start);
         Ok(Token::Cdata { text, span })
@@ -1158,7 +1381,7 @@ impl<'a> Tokenizer<'a> {
                     s.gen_text_pos_from(attr_start),
                 ));
             } else {
-                return Err(StreamError::UnexpectedEndOfStream);
+                return Err(StreamError::UnexpectedEndOfStream(s.gen_text_pos()));
             }
         }
 
@@ -1190,15 +1413,18 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn parse_text_impl(s: &mut Stream<'a>) -> StreamResult<Token<'a>> {
-        let text = s.consume_chars(|_, c| c != '<')?;
+        let start = s.pos();
+        let text = s.consume_until(b"<")?;
 
         // According to the spec, `]]>` must not appear inside a Text node.
         // https://www.w3.org/TR/xml/#syntax
         //
         // Search for `>` first, since it's a bit faster than looking for `]]>`.
         if let Some(position) = text.as_str().find('>') {
-            if text.as_str()[position.saturating_sub(2)..].starts_with("]]>") {
-                return Err(StreamError::InvalidCharacterData);
+            let position = position.saturating_sub(2);
+            if text.as_str()[position..].starts_with("]]>") {
+                let pos = s.gen_text_pos_from(start + position);
+                return Err(StreamError::InvalidCharacterData(pos));
             }
         }
 
@@ -1221,6 +1447,15 @@ impl<'a> Iterator for Tokenizer<'a> {
             t = self.parse_next_impl();
         }
 
+        // All conditional comments must be closed before the end of the document.
+        if t.is_none() && self.state != State::End {
+            if let Some(start) = self.conditionals.last() {
+                let pos = self.stream.gen_text_pos_from(start.start());
+                let e = StreamError::UnclosedConditionalComment(pos);
+                t = Some(Err(Error::InvalidConditionalComment(e, pos)));
+            }
+        }
+
         if let Some(Err(_)) = t {
             self.stream.jump_to_end();
             self.state = State::End;
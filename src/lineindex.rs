@@ -0,0 +1,155 @@
+use std::vec::Vec;
+
+use crate::{ColumnUnit, TextPos};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct Line {
+    start: usize,
+    ascii: bool,
+}
+
+/// A precomputed index of line starts.
+///
+/// Unlike `Stream::gen_text_pos_from`, which rescans the text on each call,
+/// converts byte offsets into positions in O(log n).
+/// Only non-ASCII lines require counting characters from the line start.
+///
+/// Rows and columns are computed exactly like `Stream::gen_text_pos` does,
+/// so the index can be used for the tokenizer error positions too,
+/// see [`Tokenizer::with_line_index`](crate::Tokenizer::with_line_index).
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{LineIndex, TextPos};
+///
+/// let index = LineIndex::new("<p>\n  текст</p>");
+/// assert_eq!(index.text_pos(6), TextPos::new(2, 3, 6));
+/// assert_eq!(index.text_pos(16), TextPos::new(2, 8, 16));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    lines: Vec<Line>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Builds an index for the `text`.
+    pub fn new(text: &'a str) -> Self {
+        let mut lines = Vec::new();
+        let mut line = Line {
+            start: 0,
+            ascii: true,
+        };
+
+        for (i, c) in text.bytes().enumerate() {
+            if c == b'\n' {
+                lines.push(line);
+                line = Line {
+                    start: i + 1,
+                    ascii: true,
+                };
+            } else if !c.is_ascii() {
+                line.ascii = false;
+            }
+        }
+        lines.push(line);
+
+        LineIndex { text, lines }
+    }
+
+    /// Returns the indexed text.
+    #[inline]
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns the number of lines.
+    ///
+    /// An empty text has a single line.
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns a position at the byte `offset`.
+    ///
+    /// Offsets past the end of the text are clamped
+    /// and offsets inside a character are moved to its start.
+    pub fn text_pos(&self, offset: usize) -> TextPos {
+        self.text_pos_in(offset, ColumnUnit::Chars)
+    }
+
+    /// Returns a position at the byte `offset`, with the column in UTF-16 code units.
+    ///
+    /// This is the default column encoding of the Language Server Protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::{LineIndex, TextPos};
+    ///
+    /// let index = LineIndex::new("<p>😀</p>");
+    /// assert_eq!(index.text_pos(7), TextPos::new(1, 5, 7));
+    /// assert_eq!(index.text_pos_utf16(7), TextPos::new(1, 6, 7));
+    /// ```
+    pub fn text_pos_utf16(&self, offset: usize) -> TextPos {
+        self.text_pos_in(offset, ColumnUnit::Utf16)
+    }
+
+    /// Returns a position at the byte `offset`, with the column in the specified unit.
+    pub fn text_pos_in(&self, offset: usize, unit: ColumnUnit) -> TextPos {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        // The first line always starts at 0, so the index is never 0.
+        let row = self.lines.partition_point(|line| line.start <= offset) - 1;
+        let line = self.lines[row];
+        // An ASCII line prefix never contains a CRLF pair, so every unit is a single byte.
+        let col = if line.ascii {
+            offset - line.start
+        } else {
+            unit.count(&self.text[line.start..offset])
+        };
+
+        TextPos::new(row as u32 + 1, col as u32 + 1, offset)
+    }
+
+    /// Returns a byte offset at the `row` and `col`, with the column in the specified unit.
+    ///
+    /// The inverse of [`text_pos_in`](Self::text_pos_in).
+    /// Rows past the end of the text are clamped to its end
+    /// and columns past the end of a line are clamped to the line end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::{ColumnUnit, LineIndex};
+    ///
+    /// let index = LineIndex::new("<p>\n😀</p>");
+    /// assert_eq!(index.offset_at(2, 3, ColumnUnit::Utf16), 8);
+    /// assert_eq!(index.offset_at(2, 100, ColumnUnit::Utf16), 12);
+    /// ```
+    pub fn offset_at(&self, row: u32, col: u32, unit: ColumnUnit) -> usize {
+        let idx = (row.max(1) - 1) as usize;
+        let line = match self.lines.get(idx) {
+            Some(line) => *line,
+            None => return self.text.len(),
+        };
+
+        let end = match self.lines.get(idx + 1) {
+            Some(next) => next.start - 1,
+            None => self.text.len(),
+        };
+
+        let count = (col.max(1) - 1) as usize;
+        let text = &self.text[line.start..end];
+        if line.ascii {
+            line.start + count.min(text.len())
+        } else {
+            line.start + unit.offset(text, count)
+        }
+    }
+}
@@ -0,0 +1,529 @@
+use core::ops::Deref;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::escape::push_decoded;
+use crate::style::quote_at;
+use crate::tags::lowercase_name;
+use crate::{resolve_url, ElementEnd, ElementStartTag, Error, Srcset, StrSpan, Token, Tokenizer};
+
+/// Elements whose content is not markup.
+const RAW_TAGS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// `link` relations that point to another document.
+const NAVIGATION_RELS: &[&str] = &[
+    "alternate",
+    "author",
+    "bookmark",
+    "canonical",
+    "help",
+    "license",
+    "next",
+    "prev",
+    "search",
+];
+
+/// What a link points to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LinkKind {
+    /// Another document, like `a[href]`, `area[href]` or `link[rel=canonical]`.
+    Navigation,
+    /// A stylesheet, `link[rel=stylesheet]`.
+    Stylesheet,
+    /// An image, like `img[src]`, `img[srcset]`, `link[rel=icon]`, `video[poster]`,
+    /// a `background` attribute or a CSS `url()` in a `style` attribute.
+    Image,
+    /// A script, `script[src]`.
+    Script,
+    /// A frame content, `iframe[src]` or `frame[src]`.
+    Frame,
+    /// A form submission target, `form[action]` or `button[formaction]`.
+    Form,
+    /// A redirect, `meta[http-equiv=refresh]`.
+    Refresh,
+    /// The document base URL, `base[href]`.
+    Base,
+    /// An audio or video, like `video[src]` or `source[src]`.
+    Media,
+    /// Any other resource, like `link[rel=preload]`, `object[data]` or `embed[src]`.
+    Resource,
+}
+
+/// A URL found in an attribute value.
+///
+/// Returned by [`Links`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Link<'a> {
+    kind: LinkKind,
+    element: StrSpan<'a>,
+    attribute: StrSpan<'a>,
+    value: StrSpan<'a>,
+    url: StrSpan<'a>,
+}
+
+impl<'a> Link<'a> {
+    /// Returns what the link points to.
+    #[inline]
+    pub fn kind(&self) -> LinkKind {
+        self.kind
+    }
+
+    /// Returns the qualified name of the element, like `img`.
+    #[inline]
+    pub fn element(&self) -> StrSpan<'a> {
+        self.element
+    }
+
+    /// Returns the qualified name of the attribute, like `src`.
+    #[inline]
+    pub fn attribute(&self) -> StrSpan<'a> {
+        self.attribute
+    }
+
+    /// Returns the whole attribute value, without quotes.
+    #[inline]
+    pub fn value(&self) -> StrSpan<'a> {
+        self.value
+    }
+
+    /// Returns the URL, as written in the attribute value.
+    ///
+    /// It's the whole value for most attributes, without the surrounding whitespace,
+    /// and a part of it for `srcset`, `style` and `meta` refresh values.
+    /// Character references are not decoded.
+    #[inline]
+    pub fn url(&self) -> StrSpan<'a> {
+        self.url
+    }
+
+    /// Checks that the URL is a part of a longer value.
+    fn is_embedded(&self) -> bool {
+        let attribute = self.attribute.as_str();
+        self.kind == LinkKind::Refresh
+            || attribute.eq_ignore_ascii_case("srcset")
+            || attribute.eq_ignore_ascii_case("style")
+    }
+}
+
+/// The URLs of an HTML document.
+///
+/// Links are listed in the document order. Attributes without a value
+/// or with an empty URL are skipped, as well as the content of
+/// `script`, `style`, `textarea` and `title` elements.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{LinkKind, Links};
+///
+/// let html = "<html><head><base href=\"https://example.com/\"/>\
+///             <link rel=\"stylesheet\" href=\"main.css\"/></head>\
+///             <body><a href=\"/about\">About</a><img srcset=\"a.png 1x, b.png 2x\"/></body></html>";
+/// let links = Links::new(html).unwrap();
+///
+/// assert_eq!(links.base().unwrap(), "https://example.com/");
+/// let urls: Vec<_> = links.iter().map(|link| (link.kind(), link.url().as_str())).collect();
+/// assert_eq!(urls, vec![
+///     (LinkKind::Base, "https://example.com/"),
+///     (LinkKind::Stylesheet, "main.css"),
+///     (LinkKind::Navigation, "/about"),
+///     (LinkKind::Image, "a.png"),
+///     (LinkKind::Image, "b.png"),
+/// ]);
+///
+/// let href = links[2].url();
+/// assert_eq!(&html[href.range()], "/about");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Links<'a> {
+    source: &'a str,
+    links: Vec<Link<'a>>,
+}
+
+impl<'a> Links<'a> {
+    /// Extracts the links from the `source` HTML.
+    ///
+    /// The `source` can be a document or a fragment, like multiple paragraphs.
+    pub fn new(source: &'a str) -> Result<Self, Error> {
+        let mut links = Vec::new();
+        let mut tokenizer = Tokenizer::from(source).with_fragment_parsing();
+        while let Some(tag) = tokenizer.next_element() {
+            let tag = tag?;
+            let name = lowercase_name(tag.prefix(), tag.local());
+            push_links(source, &tag, &name, &mut links);
+
+            if !tag.is_self_closing() && RAW_TAGS.contains(&name.as_str()) {
+                skip_raw(&mut tokenizer, &name)?;
+            }
+        }
+
+        Ok(Links { source, links })
+    }
+
+    /// Returns the source HTML.
+    #[inline]
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the URL of the first `base` element with an `href`, if any.
+    ///
+    /// Like in browsers, the other `base` elements are ignored.
+    pub fn base(&self) -> Option<StrSpan<'a>> {
+        self.links
+            .iter()
+            .find(|link| link.kind == LinkKind::Base)
+            .map(|link| link.url)
+    }
+
+    /// Returns the URL that the relative links are resolved against.
+    ///
+    /// It's the document `<base href>` resolved against the document `url`,
+    /// or the document `url` itself when there is no `base` element.
+    pub fn base_url(&self, url: Option<&str>) -> Option<String> {
+        let base = self.base().map(|base| decode(base.as_str()));
+        match (url, base) {
+            (Some(url), Some(base)) => Some(resolve_url(url, &base)),
+            (Some(url), None) => Some(String::from(url)),
+            (None, base) => base,
+        }
+    }
+
+    /// Returns the URL of the `link`, with the character references decoded,
+    /// resolved against the [base URL](Links::base_url) of the document at `url`.
+    ///
+    /// The URL of a `base` element is resolved against the document `url` only.
+    pub fn resolve(&self, link: &Link, url: Option<&str>) -> String {
+        let target = decode(link.url.as_str());
+        let base = match link.kind {
+            LinkKind::Base => url.map(String::from),
+            _ => self.base_url(url),
+        };
+        match base {
+            Some(base) => resolve_url(&base, &target),
+            None => target,
+        }
+    }
+
+    /// Returns the source with the link URLs replaced, leaving the rest of the document as is.
+    ///
+    /// The `f` closure returns the new URL of a link, if any, as a plain text.
+    /// It's escaped for the quote character of the attribute, and the whitespace,
+    /// quotes and parentheses of the URLs in `srcset`, `style` and `meta` refresh values
+    /// are percent-encoded, so the value keeps its meaning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::{LinkKind, Links};
+    ///
+    /// let html = "<p><a href=\"/about\">About</a><img srcset=\"a.png 1x, b.png 2x\"/></p>";
+    /// let links = Links::new(html).unwrap();
+    /// let html = links.rewrite(|link| match link.kind() {
+    ///     LinkKind::Image => Some(links.resolve(link, Some("https://cdn.example.com/img/"))),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(
+    ///     html,
+    ///     "<p><a href=\"/about\">About</a>\
+    ///      <img srcset=\"https://cdn.example.com/img/a.png 1x, https://cdn.example.com/img/b.png 2x\"/></p>"
+    /// );
+    /// ```
+    pub fn rewrite<F>(&self, mut f: F) -> String
+    where
+        F: FnMut(&Link<'a>) -> Option<String>,
+    {
+        let mut out = String::with_capacity(self.source.len());
+        let mut pos = 0;
+        for link in &self.links {
+            if let Some(url) = f(link) {
+                out.push_str(&self.source[pos..link.url.start()]);
+                let quote = self.source[link.value.end()..].chars().next();
+                push_url(&mut out, &url, quote.unwrap_or('"'), link.is_embedded());
+                pos = link.url.end();
+            }
+        }
+        out.push_str(&self.source[pos..]);
+        out
+    }
+}
+
+impl<'a> Deref for Links<'a> {
+    type Target = [Link<'a>];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.links
+    }
+}
+
+impl<'a> IntoIterator for Links<'a> {
+    type Item = Link<'a>;
+    type IntoIter = std::vec::IntoIter<Link<'a>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.links.into_iter()
+    }
+}
+
+impl<'l, 'a> IntoIterator for &'l Links<'a> {
+    type Item = &'l Link<'a>;
+    type IntoIter = core::slice::Iter<'l, Link<'a>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.links.iter()
+    }
+}
+
+/// Skips the content of a raw text element, up to its end tag.
+fn skip_raw(tokenizer: &mut Tokenizer, name: &str) -> Result<(), Error> {
+    for token in tokenizer {
+        if let Token::ElementEnd {
+            end: ElementEnd::Close(prefix, local),
+            ..
+        } = token?
+        {
+            if lowercase_name(prefix, local) == name {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the qualified name as written in the input.
+fn source_name<'a>(source: &'a str, prefix: StrSpan<'a>, local: StrSpan<'a>) -> StrSpan<'a> {
+    if prefix.is_empty() {
+        local
+    } else {
+        StrSpan::from_substr(source, prefix.start(), local.end())
+    }
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+fn decode(text: &str) -> String {
+    let mut out = String::new();
+    push_decoded(&mut out, text);
+    out
+}
+
+/// Appends the `url` escaped for an attribute value quoted with `quote`.
+///
+/// The whitespace, quotes and parentheses of an `embedded` URL are percent-encoded.
+fn push_url(out: &mut String, url: &str, quote: char, embedded: bool) {
+    for c in url.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '"' | '\'' | '(' | ')' if embedded => out.push_str(&std::format!("%{:02X}", c as u32)),
+            _ if embedded && is_space(c) => out.push_str(&std::format!("%{:02X}", c as u32)),
+            '"' if quote == '"' => out.push_str("&quot;"),
+            '\'' if quote == '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Returns the value without the surrounding whitespace.
+fn trim(value: StrSpan) -> StrSpan {
+    let text = value.as_str();
+    let start = text.len() - text.trim_start_matches(is_space).len();
+    let end = text.trim_end_matches(is_space).len().max(start);
+    value.subspan(start, end)
+}
+
+/// Returns the value of the attribute with the `name`, ignoring the case.
+fn find_attribute<'a>(tag: &ElementStartTag<'a>, name: &str) -> Option<StrSpan<'a>> {
+    tag.attributes()
+        .find(|attr| attr.prefix.is_empty() && attr.local.as_str().eq_ignore_ascii_case(name))
+        .and_then(|attr| attr.value)
+}
+
+fn has_token(value: Option<StrSpan>, token: &str) -> bool {
+    value.is_some_and(|value| {
+        value
+            .as_str()
+            .split(is_space)
+            .any(|item| item.eq_ignore_ascii_case(token))
+    })
+}
+
+/// Returns the kind of a `link` element.
+fn link_kind(tag: &ElementStartTag) -> LinkKind {
+    let rels: Vec<String> = find_attribute(tag, "rel")
+        .map_or("", |rel| rel.as_str())
+        .split(is_space)
+        .map(str::to_ascii_lowercase)
+        .collect();
+
+    if rels.iter().any(|rel| rel == "stylesheet") {
+        LinkKind::Stylesheet
+    } else if rels
+        .iter()
+        .any(|rel| rel == "icon" || rel.ends_with("-icon"))
+    {
+        LinkKind::Image
+    } else if rels
+        .iter()
+        .any(|rel| NAVIGATION_RELS.contains(&rel.as_str()))
+    {
+        LinkKind::Navigation
+    } else {
+        LinkKind::Resource
+    }
+}
+
+/// Returns the kind of the URL in the attribute, if any.
+fn attribute_kind(tag: &ElementStartTag, element: &str, attribute: &str) -> Option<LinkKind> {
+    let kind = match (element, attribute) {
+        ("a", "href") | ("a", "xlink:href") | ("area", "href") => LinkKind::Navigation,
+        ("link", "href") => link_kind(tag),
+        ("img", "src") | ("img", "srcset") | ("source", "srcset") | ("video", "poster") => {
+            LinkKind::Image
+        }
+        ("input", "src") | ("image", "href") | ("image", "xlink:href") => LinkKind::Image,
+        (_, "background") => LinkKind::Image,
+        ("script", "src") | ("script", "xlink:href") => LinkKind::Script,
+        ("iframe", "src") | ("frame", "src") => LinkKind::Frame,
+        ("form", "action") | ("button", "formaction") | ("input", "formaction") => LinkKind::Form,
+        ("audio", "src") | ("video", "src") | ("source", "src") | ("track", "src") => {
+            LinkKind::Media
+        }
+        ("embed", "src") | ("object", "data") | ("use", "href") | ("use", "xlink:href") => {
+            LinkKind::Resource
+        }
+        ("base", "href") => LinkKind::Base,
+        ("meta", "content") if has_token(find_attribute(tag, "http-equiv"), "refresh") => {
+            LinkKind::Refresh
+        }
+        (_, "style") => LinkKind::Image,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+fn push_links<'a>(
+    source: &'a str,
+    tag: &ElementStartTag<'a>,
+    name: &str,
+    links: &mut Vec<Link<'a>>,
+) {
+    let element = source_name(source, tag.prefix(), tag.local());
+    for attr in tag.attributes() {
+        let value = match attr.value {
+            Some(value) => value,
+            None => continue,
+        };
+
+        let attribute = lowercase_name(attr.prefix, attr.local);
+        let kind = match attribute_kind(tag, name, &attribute) {
+            Some(kind) => kind,
+            None => continue,
+        };
+
+        let mut urls = Vec::new();
+        match attribute.as_str() {
+            "srcset" => urls.extend(Srcset::new(value).map(|candidate| candidate.url())),
+            "style" => css_urls(value, &mut urls),
+            _ if kind == LinkKind::Refresh => urls.extend(refresh_url(value)),
+            _ => urls.push(trim(value)),
+        }
+
+        let attribute = source_name(source, attr.prefix, attr.local);
+        links.extend(
+            urls.into_iter()
+                .filter(|url| !url.is_empty())
+                .map(|url| Link {
+                    kind,
+                    element,
+                    attribute,
+                    value,
+                    url,
+                }),
+        );
+    }
+}
+
+/// Returns where the quoted text at the start of `text` ends, before and after the closing quote.
+fn quoted_end(text: &str, quote: (char, usize)) -> (usize, usize) {
+    let mut chars = text[quote.1..].char_indices();
+    while let Some((idx, c)) = chars.next() {
+        let idx = quote.1 + idx;
+        if c == '\\' {
+            chars.next();
+            continue;
+        }
+        if let Some((end, len)) = quote_at(text, idx) {
+            if end == quote.0 {
+                return (idx, idx + len);
+            }
+        }
+    }
+    (text.len(), text.len())
+}
+
+/// Collects the `url()` values of a CSS declaration list.
+fn css_urls<'a>(value: StrSpan<'a>, urls: &mut Vec<StrSpan<'a>>) {
+    let text = value.as_str();
+    let mut idx = 0;
+    while let Some(pos) = find_ignore_case(&text[idx..], "url(") {
+        let start = idx + pos + 4;
+        let rest = &text[start..];
+        let skipped = rest.len() - rest.trim_start_matches(is_space).len();
+        let start = start + skipped;
+        let rest = &text[start..];
+
+        let (url_start, url_end, end) = match quote_at(rest, 0) {
+            Some(quote) => {
+                let (url_end, end) = quoted_end(rest, quote);
+                (start + quote.1, start + url_end, start + end)
+            }
+            None => {
+                let len = rest.find(|c| c == ')' || is_space(c)).unwrap_or(rest.len());
+                (start, start + len, start + len)
+            }
+        };
+
+        urls.push(trim(value.subspan(url_start, url_end.max(url_start))));
+        idx = end;
+    }
+}
+
+fn find_ignore_case(text: &str, pattern: &str) -> Option<usize> {
+    text.as_bytes()
+        .windows(pattern.len())
+        .position(|window| window.eq_ignore_ascii_case(pattern.as_bytes()))
+}
+
+/// Returns the URL of a `meta` refresh value, like `5; url=https://example.com/`.
+fn refresh_url(value: StrSpan) -> Option<StrSpan> {
+    let text = value.as_str();
+    let mut idx = text
+        .find(|c: char| !is_space(c) && !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+
+    let rest = &text[idx..];
+    let rest = rest.trim_start_matches(|c| is_space(c) || c == ';' || c == ',');
+    idx = text.len() - rest.len();
+
+    if rest.len() >= 3 && rest[..3].eq_ignore_ascii_case("url") {
+        let after = rest[3..].trim_start_matches(is_space);
+        if let Some(after) = after.strip_prefix('=') {
+            let after = after.trim_start_matches(is_space);
+            idx = text.len() - after.len();
+        }
+    }
+
+    let rest = &text[idx..];
+    match quote_at(rest, 0) {
+        Some(quote) => {
+            let (end, _) = quoted_end(rest, quote);
+            Some(trim(value.subspan(idx + quote.1, idx + end)))
+        }
+        None => Some(trim(value.subspan(idx, text.len()))),
+    }
+}
@@ -0,0 +1,487 @@
+use core::fmt;
+use core::ops::Range;
+use std::borrow::ToOwned;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use serde_json::{json, Value};
+
+use crate::diagnostic::Help;
+use crate::tags::is_void;
+use crate::{ColumnUnit, ElementEnd, Error, Errors, LineIndex, StrSpan, Token, Tokenizer};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const SYMBOL_KIND_OBJECT: u32 = 19;
+const SEVERITY_ERROR: u32 = 1;
+
+/// A Language Server Protocol server for HTML documents.
+///
+/// Supports:
+///
+/// - parsing errors as diagnostics
+/// - document symbols from the elements structure
+/// - folding ranges of elements, comments and conditional comments
+/// - go to definition on a tag name, which jumps to the matching tag
+///
+/// Only the full text document synchronization is supported.
+/// Positions are reported in UTF-16 code units.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::LspServer;
+///
+/// let body = r#"{"jsonrpc":"2.0","method":"exit"}"#;
+/// let input = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+///
+/// let mut output = Vec::new();
+/// LspServer::new().run(input.as_bytes(), &mut output).unwrap();
+/// assert!(output.is_empty());
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct LspServer {
+    documents: HashMap<String, String>,
+    shutdown: bool,
+}
+
+impl LspServer {
+    /// Constructs a new `LspServer`.
+    pub fn new() -> Self {
+        LspServer::default()
+    }
+
+    /// Serves the messages from the `input` until the `exit` notification
+    /// or the end of the input.
+    ///
+    /// Messages are framed with the `Content-Length` header, like on the stdio transport.
+    pub fn run<R: BufRead, W: Write>(&mut self, mut input: R, mut output: W) -> io::Result<()> {
+        while let Some(body) = read_message(&mut input)? {
+            let message = match serde_json::from_slice::<Value>(&body) {
+                Ok(message) => message,
+                Err(e) => {
+                    let reply = error_response(Value::Null, PARSE_ERROR, &e.to_string());
+                    write_message(&mut output, &reply)?;
+                    continue;
+                }
+            };
+
+            if message["method"] == "exit" {
+                break;
+            }
+
+            for reply in self.handle(&message) {
+                write_message(&mut output, &reply)?;
+            }
+        }
+
+        output.flush()
+    }
+
+    /// Handles a single message and returns the messages to send back.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = match message["method"].as_str() {
+            Some(method) => method,
+            // Responses to server requests are not expected.
+            None => return Vec::new(),
+        };
+
+        let params = &message["params"];
+        match message.get("id") {
+            Some(id) => {
+                let reply = match self.request(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, text)) => error_response(id.clone(), code, text),
+                };
+
+                std::vec![reply]
+            }
+            None => self.notification(method, params),
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, &'static str)> {
+        if self.shutdown {
+            return Err((INVALID_REQUEST, "the server is shut down"));
+        }
+
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "documentSymbolProvider": true,
+                    "foldingRangeProvider": true,
+                    "definitionProvider": true,
+                },
+                "serverInfo": {
+                    "name": "htmlparser",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/documentSymbol" => {
+                let (_, text) = self.document(params)?;
+                let outline = Outline::new(text);
+                let index = LineIndex::new(text);
+                Ok(outline.symbols(&index, &outline.roots))
+            }
+            "textDocument/foldingRange" => {
+                let (_, text) = self.document(params)?;
+                let index = LineIndex::new(text);
+                Ok(Value::Array(Outline::new(text).folding_ranges(&index)))
+            }
+            "textDocument/definition" => {
+                let (uri, text) = self.document(params)?;
+                let index = LineIndex::new(text);
+                let position = &params["position"];
+                let (line, character) =
+                    match (position["line"].as_u64(), position["character"].as_u64()) {
+                        (Some(line), Some(character)) => (line as u32, character as u32),
+                        _ => return Err((INVALID_PARAMS, "invalid position")),
+                    };
+
+                let offset = index.offset_at(line + 1, character + 1, ColumnUnit::Utf16);
+                let location = Outline::new(text)
+                    .matching_tag(offset)
+                    .map(|range| json!({ "uri": uri, "range": lsp_range(&index, range) }));
+                Ok(location.unwrap_or(Value::Null))
+            }
+            _ => Err((METHOD_NOT_FOUND, "method not found")),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = match params["textDocument"]["uri"].as_str() {
+            Some(uri) => uri.to_owned(),
+            None => return Vec::new(),
+        };
+
+        let text = match method {
+            "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+            // With the full synchronization, the last change contains the whole text.
+            "textDocument/didChange" => params["contentChanges"]
+                .as_array()
+                .and_then(|changes| changes.last())
+                .and_then(|change| change["text"].as_str()),
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return std::vec![publish_diagnostics(&uri, Vec::new())];
+            }
+            _ => None,
+        };
+
+        match text {
+            Some(text) => {
+                let diagnostics = diagnostics(text);
+                self.documents.insert(uri.clone(), text.to_owned());
+                std::vec![publish_diagnostics(&uri, diagnostics)]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn document(&self, params: &Value) -> Result<(&str, &str), (i64, &'static str)> {
+        params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|uri| self.documents.get_key_value(uri))
+            .map(|(uri, text)| (uri.as_str(), text.as_str()))
+            .ok_or((INVALID_PARAMS, "unknown document"))
+    }
+}
+
+fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut len = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                len = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let len = match len {
+        Some(len) => len,
+        None => {
+            let e = "a message without the Content-Length header";
+            return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+        }
+    };
+
+    let mut body = std::vec![0; len];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn lsp_position(index: &LineIndex, offset: usize) -> Value {
+    let pos = index.text_pos_utf16(offset);
+    json!({ "line": pos.row - 1, "character": pos.col - 1 })
+}
+
+fn lsp_range(index: &LineIndex, range: Range<usize>) -> Value {
+    json!({
+        "start": lsp_position(index, range.start),
+        "end": lsp_position(index, range.end),
+    })
+}
+
+/// An error message with a fix suggestion, but without the position.
+struct Message(Error);
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.message())?;
+        if let Some(help) = Help::new(self.0) {
+            write!(f, "\nhelp: {}", help)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn diagnostics(text: &str) -> Vec<Value> {
+    let index = LineIndex::new(text);
+    Errors::new(text)
+        .with_line_index(&index)
+        .map(|e| {
+            // Underline the parsed part of the token and the unexpected character.
+            let range = e.range();
+            let end = match text[range.end..].chars().next() {
+                Some(c) => range.end + c.len_utf8(),
+                None => range.end,
+            };
+
+            json!({
+                "range": lsp_range(&index, range.start..end),
+                "severity": SEVERITY_ERROR,
+                "source": "htmlparser",
+                "message": Message(e).to_string(),
+            })
+        })
+        .collect()
+}
+
+struct Element<'a> {
+    name: &'a str,
+    name_range: Range<usize>,
+    range: Range<usize>,
+    close_name_range: Option<Range<usize>>,
+    children: Vec<usize>,
+}
+
+/// The elements tree and the foldable regions of a document.
+///
+/// Built from the tokens preceding the first error.
+struct Outline<'a> {
+    elements: Vec<Element<'a>>,
+    roots: Vec<usize>,
+    comments: Vec<Range<usize>>,
+    conditionals: Vec<Range<usize>>,
+}
+
+fn qualified_name<'a>(text: &'a str, prefix: StrSpan, local: StrSpan) -> (&'a str, Range<usize>) {
+    let start = if prefix.is_empty() {
+        local.start()
+    } else {
+        prefix.start()
+    };
+
+    (&text[start..local.end()], start..local.end())
+}
+
+impl<'a> Outline<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut outline = Outline {
+            elements: Vec::new(),
+            roots: Vec::new(),
+            comments: Vec::new(),
+            conditionals: Vec::new(),
+        };
+        let mut stack: Vec<usize> = Vec::new();
+        let mut void = false;
+
+        for token in Tokenizer::from(text) {
+            let token = match token {
+                Ok(token) => token,
+                Err(_) => break,
+            };
+
+            match token {
+                Token::ElementStart {
+                    prefix,
+                    local,
+                    span,
+                } => {
+                    let (name, name_range) = qualified_name(text, prefix, local);
+                    void = is_void(prefix, local);
+                    let idx = outline.elements.len();
+                    outline.elements.push(Element {
+                        name,
+                        name_range,
+                        range: span.range(),
+                        close_name_range: None,
+                        children: Vec::new(),
+                    });
+
+                    match stack.last() {
+                        Some(&parent) => outline.elements[parent].children.push(idx),
+                        None => outline.roots.push(idx),
+                    }
+                    stack.push(idx);
+                }
+                Token::ElementEnd { end, span } => match end {
+                    ElementEnd::Open if !void => {
+                        if let Some(&idx) = stack.last() {
+                            outline.elements[idx].range.end = span.end();
+                        }
+                    }
+                    ElementEnd::Open | ElementEnd::Empty => {
+                        if let Some(idx) = stack.pop() {
+                            outline.elements[idx].range.end = span.end();
+                        }
+                    }
+                    ElementEnd::Close(prefix, local) => {
+                        let (name, name_range) = qualified_name(text, prefix, local);
+                        let depth = stack
+                            .iter()
+                            .rposition(|&idx| outline.elements[idx].name == name);
+
+                        if let Some(depth) = depth {
+                            // Elements without a close tag, like `<li>`,
+                            // end where the parent does.
+                            for idx in stack.drain(depth + 1..) {
+                                outline.elements[idx].range.end = span.start();
+                            }
+
+                            if let Some(idx) = stack.pop() {
+                                let element = &mut outline.elements[idx];
+                                element.range.end = span.end();
+                                element.close_name_range = Some(name_range);
+                            }
+                        }
+                    }
+                },
+                Token::Comment { span, .. } => outline.comments.push(span.range()),
+                Token::ConditionalCommentEnd { start, span } => {
+                    outline.conditionals.push(start.start()..span.end());
+                }
+                _ => {}
+            }
+        }
+
+        for idx in stack {
+            outline.elements[idx].range.end = text.len();
+        }
+
+        outline
+    }
+
+    fn symbols(&self, index: &LineIndex, elements: &[usize]) -> Value {
+        let symbols = elements
+            .iter()
+            .map(|&idx| {
+                let element = &self.elements[idx];
+                json!({
+                    "name": element.name,
+                    "kind": SYMBOL_KIND_OBJECT,
+                    "range": lsp_range(index, element.range.clone()),
+                    "selectionRange": lsp_range(index, element.name_range.clone()),
+                    "children": self.symbols(index, &element.children),
+                })
+            })
+            .collect();
+
+        Value::Array(symbols)
+    }
+
+    fn folding_ranges(&self, index: &LineIndex) -> Vec<Value> {
+        let mut ranges = Vec::new();
+        let mut push = |range: &Range<usize>, kind: Option<&str>, keep_last_line: bool| {
+            let start = index.text_pos(range.start).row - 1;
+            let mut end = index.text_pos(range.end).row - 1;
+            // Keep the closing tag visible, like editors do for brackets.
+            if keep_last_line {
+                end = end.saturating_sub(1);
+            }
+
+            if end > start {
+                let mut range = json!({ "startLine": start, "endLine": end });
+                if let Some(kind) = kind {
+                    range["kind"] = kind.into();
+                }
+                ranges.push((start, range));
+            }
+        };
+
+        for element in &self.elements {
+            if let Some(ref close) = element.close_name_range {
+                push(&(element.range.start..close.start), None, true);
+            }
+        }
+
+        for range in &self.comments {
+            push(range, Some("comment"), false);
+        }
+
+        for range in &self.conditionals {
+            push(range, Some("region"), true);
+        }
+
+        ranges.sort_by_key(|&(start, _)| start);
+        ranges.into_iter().map(|(_, range)| range).collect()
+    }
+
+    // Returns the name range of the tag matching the one at the `offset`.
+    fn matching_tag(&self, offset: usize) -> Option<Range<usize>> {
+        let contains = |range: &Range<usize>| range.start <= offset && offset <= range.end;
+        self.elements.iter().find_map(|element| {
+            let close = element.close_name_range.clone()?;
+            if contains(&element.name_range) {
+                Some(close)
+            } else if contains(&close) {
+                Some(element.name_range.clone())
+            } else {
+                None
+            }
+        })
+    }
+}
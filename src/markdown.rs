@@ -0,0 +1,596 @@
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use crate::escape::{push_decoded, reference_len};
+use crate::tree::{self, Element, Node};
+use crate::Error;
+
+/// Elements whose content is not parsed.
+const RAW_TAGS: &[&str] = &["script", "style", "textarea"];
+
+/// Elements that are dropped with their content.
+const SKIPPED_TAGS: &[&str] = &["head", "script", "style", "template", "title"];
+
+/// Block elements that only group their children.
+const CONTAINER_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "body",
+    "center",
+    "div",
+    "figcaption",
+    "figure",
+    "footer",
+    "header",
+    "html",
+    "main",
+    "nav",
+    "section",
+];
+
+/// Inline elements that have no Markdown equivalent but don't need one.
+const TRANSPARENT_TAGS: &[&str] = &[
+    "abbr", "big", "cite", "font", "label", "small", "span", "time",
+];
+
+/// An HTML to Markdown converter.
+///
+/// Headings, paragraphs, emphasis, links, images, nested lists, code blocks,
+/// tables and blockquotes are converted to their CommonMark and GitHub Flavored Markdown
+/// equivalents. Other elements are copied as HTML, which Markdown allows.
+///
+/// - `head`, `script`, `style`, `template` and `title` elements are dropped,
+///   as well as DOCTYPE, processing instructions and conditional comment markers.
+/// - Whitespace is collapsed, except inside `pre` elements.
+/// - Character references are decoded, and the characters that have a meaning
+///   in Markdown are escaped.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::MarkdownConverter;
+///
+/// let text = "<div><h1>Title</h1><p>Some <b>bold</b> and a <a href=\"/x\">link</a>.</p>\
+///             <ul><li>One</li><li>Two</li></ul></div>";
+/// assert_eq!(
+///     MarkdownConverter::new().convert(text).unwrap(),
+///     "# Title\n\nSome **bold** and a [link](/x).\n\n- One\n- Two\n"
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MarkdownConverter {
+    bullet: char,
+}
+
+impl Default for MarkdownConverter {
+    fn default() -> Self {
+        MarkdownConverter { bullet: '-' }
+    }
+}
+
+impl MarkdownConverter {
+    /// Creates a converter that uses `-` as the list bullet.
+    pub fn new() -> Self {
+        MarkdownConverter::default()
+    }
+
+    /// Sets the bullet of the unordered list items, like `-`, `*` or `+`.
+    pub fn with_bullet(mut self, bullet: char) -> Self {
+        self.bullet = bullet;
+        self
+    }
+
+    /// Returns the `text` converted to Markdown.
+    ///
+    /// The `text` can be a document or a fragment, like multiple paragraphs.
+    pub fn convert(&self, text: &str) -> Result<String, Error> {
+        let nodes = tree::parse(text, RAW_TAGS)?;
+
+        let converter = Converter {
+            options: self,
+            text,
+        };
+        let mut out = converter.blocks(&nodes, false);
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// A converted block, with whether it's a list.
+type Block = (String, bool);
+
+struct Converter<'a, 'o> {
+    options: &'o MarkdownConverter,
+    text: &'a str,
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+fn push_space(out: &mut String) {
+    if !out.ends_with(' ') && !out.ends_with('\n') {
+        out.push(' ');
+    }
+}
+
+/// Appends the decoded `text` with the whitespace collapsed.
+fn push_collapsed(out: &mut String, text: &str) {
+    let mut decoded = String::new();
+    push_decoded(&mut decoded, text);
+    for c in decoded.chars() {
+        if is_space(c) {
+            push_space(out);
+        } else {
+            out.push(c);
+        }
+    }
+}
+
+/// Appends the decoded `text` with the whitespace collapsed
+/// and the Markdown characters escaped.
+fn push_text(out: &mut String, text: &str) {
+    let mut decoded = String::new();
+    push_decoded(&mut decoded, text);
+    for (idx, c) in decoded.char_indices() {
+        match c {
+            _ if is_space(c) => push_space(out),
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '&' if reference_len(&decoded[idx..]).is_some() => out.push_str("\\&"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Escapes the characters that would start a block at the beginning of a line.
+fn escape_line_start(line: &str) -> String {
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    let mut out = String::with_capacity(line.len() + 1);
+    match line.as_bytes().get(digits) {
+        Some(b'.') | Some(b')') if digits != 0 => {
+            out.push_str(&line[..digits]);
+            out.push('\\');
+            out.push_str(&line[digits..]);
+        }
+        Some(b'#') | Some(b'>') | Some(b'-') | Some(b'+') | Some(b'=') if digits == 0 => {
+            out.push('\\');
+            out.push_str(line);
+        }
+        _ => out.push_str(line),
+    }
+    out
+}
+
+/// Prefixes each line but the first one with the `indent`.
+fn indent_lines(text: &str, first: &str, indent: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (idx, line) in text.split('\n').enumerate() {
+        if idx == 0 {
+            out.push_str(first);
+        } else {
+            out.push('\n');
+            if !line.is_empty() {
+                out.push_str(indent);
+            }
+        }
+        out.push_str(line);
+    }
+    out.trim_end_matches(' ').to_string()
+}
+
+/// Appends a link or an image destination, with the optional title.
+fn push_destination(out: &mut String, url: &str, title: Option<&str>) {
+    let mut decoded = String::new();
+    push_decoded(&mut decoded, url.trim());
+    out.push('(');
+    if decoded.contains(|c| is_space(c) || c == '(' || c == ')') {
+        out.push('<');
+        out.push_str(&decoded.replace('<', "%3C").replace('>', "%3E"));
+        out.push('>');
+    } else {
+        out.push_str(&decoded);
+    }
+    if let Some(title) = title.filter(|title| !title.is_empty()) {
+        let mut decoded = String::new();
+        push_decoded(&mut decoded, title);
+        out.push_str(" \"");
+        out.push_str(&decoded.replace('"', "\\\""));
+        out.push('"');
+    }
+    out.push(')');
+}
+
+/// Returns the language of a code block from a `language-*` or `lang-*` class.
+fn code_language<'a>(element: &Element<'a>) -> Option<&'a str> {
+    element
+        .attribute("class")?
+        .split_ascii_whitespace()
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+        })
+}
+
+/// Appends the decoded text content, without collapsing the whitespace.
+fn push_content(out: &mut String, nodes: &[Node]) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => push_decoded(out, text.as_str()),
+            Node::Element(element) if element.lowercase == "br" => out.push('\n'),
+            Node::Element(element) => match element.raw {
+                Some(raw) => out.push_str(raw),
+                None => push_content(out, &element.children),
+            },
+            Node::Inline(_) | Node::Block(_) => {}
+        }
+    }
+}
+
+impl Converter<'_, '_> {
+    /// Returns the source HTML of the element.
+    fn source(&self, element: &Element) -> String {
+        self.text[element.span.clone()].to_string()
+    }
+
+    /// Converts the nodes to blocks separated by empty lines, grouping the inline ones into paragraphs.
+    ///
+    /// In a `tight` list item, lists are separated by a single line break.
+    fn blocks(&self, nodes: &[Node], tight: bool) -> String {
+        let mut blocks: Vec<Block> = Vec::new();
+        let mut start = 0;
+        for (idx, node) in nodes.iter().enumerate() {
+            if node.is_block() {
+                blocks.extend(self.paragraph(&nodes[start..idx]));
+                blocks.extend(self.block(node));
+                start = idx + 1;
+            }
+        }
+        blocks.extend(self.paragraph(&nodes[start..]));
+
+        let mut out = String::new();
+        let mut previous_list = false;
+        for (idx, (block, list)) in blocks.iter().enumerate() {
+            if idx != 0 {
+                out.push_str(if tight && (previous_list || *list) {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            out.push_str(block);
+            previous_list = *list;
+        }
+        out
+    }
+
+    fn paragraph(&self, nodes: &[Node]) -> Option<Block> {
+        let mut text = String::new();
+        self.inline(nodes, &mut text);
+        let text = text.trim_matches(' ');
+        if text.is_empty() {
+            return None;
+        }
+
+        let lines: Vec<String> = text
+            .split('\n')
+            .map(|line| escape_line_start(line.trim_start_matches(' ')))
+            .collect();
+        Some((lines.join("\n"), false))
+    }
+
+    fn block(&self, node: &Node) -> Option<Block> {
+        let element = match node {
+            Node::Element(element) => element,
+            Node::Text(_) | Node::Inline(_) | Node::Block(_) => return None,
+        };
+
+        let name = element.lowercase.as_str();
+        let block = match name {
+            _ if SKIPPED_TAGS.contains(&name) => return None,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let mut text = String::new();
+                self.inline(&element.children, &mut text);
+                let text = text.replace("  \n", " ");
+                let text = text.trim_matches(|c| c == ' ' || c == '\n');
+                if text.is_empty() {
+                    return None;
+                }
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                let mut out = "#".repeat(level);
+                out.push(' ');
+                out.push_str(text);
+                out
+            }
+            "p" | "li" => self.blocks(&element.children, false),
+            "blockquote" => {
+                let text = self.blocks(&element.children, false);
+                let lines: Vec<&str> = text.split('\n').collect();
+                let lines: Vec<String> = lines
+                    .iter()
+                    .map(|line| match line.is_empty() {
+                        true => String::from(">"),
+                        false => std::format!("> {}", line),
+                    })
+                    .collect();
+                lines.join("\n")
+            }
+            "ul" | "ol" => return self.list(element, name == "ol"),
+            "pre" => self.code_block(element),
+            "table" => self.table(element).unwrap_or_else(|| self.source(element)),
+            "hr" => String::from("---"),
+            _ if CONTAINER_TAGS.contains(&name) => self.blocks(&element.children, false),
+            _ if self.is_inline(name) => return self.paragraph(core::slice::from_ref(node)),
+            _ => self.source(element),
+        };
+
+        match block.is_empty() {
+            true => None,
+            false => Some((block, false)),
+        }
+    }
+
+    fn list(&self, element: &Element, ordered: bool) -> Option<Block> {
+        let mut number: u64 = element
+            .attribute("start")
+            .and_then(|start| start.trim().parse().ok())
+            .unwrap_or(1);
+
+        let mut bullet = String::new();
+        bullet.push(self.options.bullet);
+        bullet.push(' ');
+
+        let mut items = Vec::new();
+        for child in &element.children {
+            let content = match child {
+                Node::Element(item) if item.lowercase == "li" => self.blocks(&item.children, true),
+                Node::Text(text) if text.as_str().trim_matches(is_space).is_empty() => continue,
+                Node::Element(_) | Node::Text(_) => match self.block(child) {
+                    Some((content, _)) => content,
+                    None => self.paragraph(core::slice::from_ref(child))?.0,
+                },
+                Node::Inline(_) | Node::Block(_) => continue,
+            };
+
+            let marker = match ordered {
+                true => std::format!("{}. ", number),
+                false => bullet.clone(),
+            };
+            number += 1;
+            let indent = " ".repeat(marker.len());
+            items.push(indent_lines(&content, &marker, &indent));
+        }
+
+        match items.is_empty() {
+            true => None,
+            false => Some((items.join("\n"), true)),
+        }
+    }
+
+    fn code_block(&self, element: &Element) -> String {
+        let mut children = element.children.iter().filter(|child| match child {
+            Node::Text(text) => !text.as_str().trim_matches(is_space).is_empty(),
+            _ => true,
+        });
+
+        let mut content = String::new();
+        let language = match (children.next(), children.next()) {
+            (Some(Node::Element(code)), None) if code.lowercase == "code" => {
+                push_content(&mut content, &code.children);
+                code_language(code).or_else(|| code_language(element))
+            }
+            _ => {
+                push_content(&mut content, &element.children);
+                code_language(element)
+            }
+        };
+
+        // A newline right after the start tag is ignored.
+        let content = content.strip_prefix('\n').unwrap_or(&content);
+        let content = content.strip_suffix('\n').unwrap_or(content);
+
+        let mut fence = String::from("```");
+        while content.contains(fence.as_str()) {
+            fence.push('`');
+        }
+
+        std::format!(
+            "{}{}\n{}\n{}",
+            fence,
+            language.unwrap_or(""),
+            content,
+            fence
+        )
+    }
+
+    fn table(&self, element: &Element) -> Option<String> {
+        let mut rows = Vec::new();
+        self.table_rows(&element.children, &mut rows);
+        let columns = rows.iter().map(Vec::len).max().filter(|len| *len != 0)?;
+
+        let mut out = String::new();
+        for (idx, row) in rows.iter().enumerate() {
+            if idx != 0 {
+                out.push('\n');
+            }
+            out.push('|');
+            for column in 0..columns {
+                out.push(' ');
+                out.push_str(row.get(column).map_or("", |(cell, _)| cell.as_str()));
+                out.push_str(" |");
+            }
+
+            if idx == 0 {
+                out.push_str("\n|");
+                for column in 0..columns {
+                    let align = row.get(column).and_then(|(_, align)| *align);
+                    out.push_str(match align {
+                        Some(align) if align.eq_ignore_ascii_case("center") => " :---: |",
+                        Some(align) if align.eq_ignore_ascii_case("right") => " ---: |",
+                        Some(align) if align.eq_ignore_ascii_case("left") => " :--- |",
+                        _ => " --- |",
+                    });
+                }
+            }
+        }
+        Some(out)
+    }
+
+    fn table_rows<'a>(&self, nodes: &[Node<'a>], rows: &mut Vec<Vec<(String, Option<&'a str>)>>) {
+        for node in nodes {
+            let element = match node {
+                Node::Element(element) => element,
+                _ => continue,
+            };
+
+            match element.lowercase.as_str() {
+                "thead" | "tbody" | "tfoot" => self.table_rows(&element.children, rows),
+                "tr" => {
+                    let mut row = Vec::new();
+                    for child in &element.children {
+                        match child {
+                            Node::Element(cell)
+                                if cell.lowercase == "td" || cell.lowercase == "th" =>
+                            {
+                                let mut text = String::new();
+                                self.inline(&cell.children, &mut text);
+                                let text = text
+                                    .trim_matches(|c| c == ' ' || c == '\n')
+                                    .replace("  \n", "<br>")
+                                    .replace('\n', " ")
+                                    .replace('|', "\\|");
+                                row.push((text, cell.attribute("align")));
+                            }
+                            _ => {}
+                        }
+                    }
+                    rows.push(row);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns whether the element is converted to inline Markdown.
+    fn is_inline(&self, name: &str) -> bool {
+        matches!(
+            name,
+            "a" | "b"
+                | "br"
+                | "code"
+                | "del"
+                | "em"
+                | "i"
+                | "img"
+                | "kbd"
+                | "s"
+                | "strike"
+                | "strong"
+                | "tt"
+        ) || TRANSPARENT_TAGS.contains(&name)
+    }
+
+    fn inline(&self, nodes: &[Node], out: &mut String) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => push_text(out, text.as_str()),
+                Node::Inline(text) => out.push_str(text.as_str()),
+                Node::Block(_) => {}
+                Node::Element(element) => self.inline_element(element, out),
+            }
+        }
+    }
+
+    fn inline_element(&self, element: &Element, out: &mut String) {
+        let name = element.lowercase.as_str();
+        match name {
+            "b" | "strong" => self.emphasis("**", element, out),
+            "i" | "em" => self.emphasis("*", element, out),
+            "s" | "del" | "strike" => self.emphasis("~~", element, out),
+            "code" | "kbd" | "tt" => {
+                let mut content = String::new();
+                push_content(&mut content, &element.children);
+                let mut text = String::new();
+                push_collapsed(&mut text, &content);
+
+                let ticks = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                let fence = "`".repeat(ticks + 1);
+                let pad = match text.starts_with('`') || text.ends_with('`') {
+                    true => " ",
+                    false => "",
+                };
+                if !text.is_empty() {
+                    out.push_str(&fence);
+                    out.push_str(pad);
+                    out.push_str(&text);
+                    out.push_str(pad);
+                    out.push_str(&fence);
+                }
+            }
+            "a" => {
+                let href = match element.attribute("href") {
+                    Some(href) => href,
+                    None => return self.inline(&element.children, out),
+                };
+
+                let mut text = String::new();
+                self.inline(&element.children, &mut text);
+                let text = text.trim_matches(' ');
+                out.push('[');
+                if text.is_empty() {
+                    push_text(out, href);
+                } else {
+                    out.push_str(text);
+                }
+                out.push(']');
+                push_destination(out, href, element.attribute("title"));
+            }
+            "img" => {
+                let src = element.attribute("src").unwrap_or("");
+                out.push_str("![");
+                push_text(out, element.attribute("alt").unwrap_or(""));
+                out.push(']');
+                push_destination(out, src, element.attribute("title"));
+            }
+            "br" => {
+                let len = out.trim_end_matches(' ').len();
+                out.truncate(len);
+                out.push_str("  \n");
+            }
+            _ if SKIPPED_TAGS.contains(&name) => {}
+            _ if TRANSPARENT_TAGS.contains(&name) || CONTAINER_TAGS.contains(&name) => {
+                self.inline(&element.children, out)
+            }
+            _ => out.push_str(&self.source(element)),
+        }
+    }
+
+    /// Wraps the content with the `delimiter`, keeping the surrounding whitespace outside.
+    fn emphasis(&self, delimiter: &str, element: &Element, out: &mut String) {
+        let mut content = String::new();
+        self.inline(&element.children, &mut content);
+        let text = content.trim_matches(' ');
+        if text.is_empty() {
+            if !content.is_empty() {
+                push_space(out);
+            }
+            return;
+        }
+
+        if content.starts_with(' ') {
+            push_space(out);
+        }
+        out.push_str(delimiter);
+        out.push_str(text);
+        out.push_str(delimiter);
+        if content.ends_with(' ') {
+            out.push(' ');
+        }
+    }
+}
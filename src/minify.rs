@@ -0,0 +1,541 @@
+use std::string::String;
+use std::vec::Vec;
+
+use crate::tags::{lowercase_name, BLOCK_TAGS, BOOLEAN_ATTRIBUTES, PREFORMATTED_TAGS, VOID_TAGS};
+use crate::{ElementEnd, EntityDefinition, Error, ExternalId, Handler, StrSpan, Tokenizer};
+
+/// Elements whose start tag closes an open `p` element.
+const P_CLOSERS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Parents of a `p` element that require its end tag.
+const P_KEEPERS: &[&str] = &["a", "audio", "del", "ins", "map", "noscript", "video"];
+
+/// An HTML minifier.
+///
+/// Tags, attributes and text are copied from the input using the token spans,
+/// so character references and attribute values are preserved as is.
+///
+/// [`Minifier::new`] only applies the changes that keep the output
+/// a valid input for the [`Tokenizer`]:
+///
+/// - Whitespace is collapsed and removed around block elements,
+///   except inside `pre`, `textarea`, `script` and `style` elements.
+/// - Comments are removed. Conditional comments are kept.
+/// - Empty and boolean attribute values are removed, like `checked="checked"`.
+/// - Tag names are lowercased.
+///
+/// [`Minifier::aggressive`] additionally removes optional attribute quotes
+/// and optional end tags, like `</li>` or `</p>`, as allowed by the HTML standard.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::Minifier;
+///
+/// let text = "<UL class='menu'>\n  <li>One</li>\n  <li>Two <b>and</b>  three</li>\n</UL>";
+/// assert_eq!(
+///     Minifier::new().minify(text).unwrap(),
+///     "<ul class='menu'><li>One</li><li>Two <b>and</b> three</li></ul>"
+/// );
+/// assert_eq!(
+///     Minifier::aggressive().minify(text).unwrap(),
+///     "<ul class=menu><li>One<li>Two <b>and</b> three</ul>"
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Minifier {
+    collapse_whitespace: bool,
+    remove_comments: bool,
+    collapse_boolean_attributes: bool,
+    lowercase_tag_names: bool,
+    remove_attribute_quotes: bool,
+    remove_optional_end_tags: bool,
+}
+
+impl Default for Minifier {
+    fn default() -> Self {
+        Minifier {
+            collapse_whitespace: true,
+            remove_comments: true,
+            collapse_boolean_attributes: true,
+            lowercase_tag_names: true,
+            remove_attribute_quotes: false,
+            remove_optional_end_tags: false,
+        }
+    }
+}
+
+impl Minifier {
+    /// Creates a minifier with the safe defaults.
+    pub fn new() -> Self {
+        Minifier::default()
+    }
+
+    /// Creates a minifier with all the options enabled.
+    pub fn aggressive() -> Self {
+        Minifier {
+            remove_attribute_quotes: true,
+            remove_optional_end_tags: true,
+            ..Minifier::default()
+        }
+    }
+
+    /// Sets whether whitespace is collapsed.
+    pub fn with_collapse_whitespace(mut self, value: bool) -> Self {
+        self.collapse_whitespace = value;
+        self
+    }
+
+    /// Sets whether comments are removed.
+    pub fn with_remove_comments(mut self, value: bool) -> Self {
+        self.remove_comments = value;
+        self
+    }
+
+    /// Sets whether empty and boolean attribute values are removed.
+    pub fn with_collapse_boolean_attributes(mut self, value: bool) -> Self {
+        self.collapse_boolean_attributes = value;
+        self
+    }
+
+    /// Sets whether tag names are lowercased.
+    pub fn with_lowercase_tag_names(mut self, value: bool) -> Self {
+        self.lowercase_tag_names = value;
+        self
+    }
+
+    /// Sets whether quotes are removed from attribute values that don't require them.
+    ///
+    /// Quotes are always kept inside `svg` and `math` elements.
+    pub fn with_remove_attribute_quotes(mut self, value: bool) -> Self {
+        self.remove_attribute_quotes = value;
+        self
+    }
+
+    /// Sets whether optional end tags are removed.
+    pub fn with_remove_optional_end_tags(mut self, value: bool) -> Self {
+        self.remove_optional_end_tags = value;
+        self
+    }
+
+    /// Returns the minified `text`.
+    ///
+    /// The `text` can be a document or a fragment, like multiple paragraphs.
+    pub fn minify(&self, text: &str) -> Result<String, Error> {
+        let mut handler = Minify {
+            options: *self,
+            out: String::with_capacity(text.len()),
+            open: Vec::new(),
+            tag: None,
+            end_tag: None,
+            space: false,
+            after_block: true,
+        };
+
+        Tokenizer::from(text)
+            .with_fragment_parsing()
+            .visit(&mut handler)?;
+        handler.flush(Next::End(None));
+        Ok(handler.out)
+    }
+}
+
+/// The token after a pending whitespace or end tag.
+#[derive(Clone, Copy)]
+enum Next<'n> {
+    Start(&'n str),
+    /// An element end tag or the end of the document.
+    End(Option<&'n str>),
+    Text,
+    Other,
+}
+
+/// An end tag that can be omitted, depending on the next token.
+struct EndTag {
+    name: String,
+    text: String,
+    parent: Option<String>,
+}
+
+impl EndTag {
+    fn can_omit(&self, next: Next) -> bool {
+        let name = self.name.as_str();
+        match next {
+            Next::Start(next) => match name {
+                "li" => next == "li",
+                "dt" | "dd" => next == "dt" || next == "dd",
+                "p" => P_CLOSERS.contains(&next),
+                "td" | "th" => next == "td" || next == "th",
+                "tr" => next == "tr",
+                "thead" | "tbody" => next == "tbody" || next == "tfoot",
+                "option" => next == "option" || next == "optgroup",
+                "optgroup" => next == "optgroup",
+                "head" => next == "body",
+                _ => false,
+            },
+            Next::End(next) => {
+                let parent = self.parent.as_deref();
+                if next.is_some() && next != parent {
+                    return false;
+                }
+
+                match name {
+                    "p" => !parent.is_some_and(|parent| P_KEEPERS.contains(&parent)),
+                    "li" | "dd" | "td" | "th" | "tr" | "tbody" | "tfoot" | "option"
+                    | "optgroup" | "body" | "html" => true,
+                    _ => false,
+                }
+            }
+            Next::Text | Next::Other => false,
+        }
+    }
+}
+
+struct Minify {
+    options: Minifier,
+    out: String,
+    /// Lowercase names of the elements that are not closed yet.
+    open: Vec<String>,
+    /// The lowercase name of the current element start.
+    tag: Option<String>,
+    /// The last end tag, when it's optional.
+    end_tag: Option<EndTag>,
+    /// Whether a collapsed whitespace is pending.
+    space: bool,
+    /// Whether the previous tag makes the following whitespace insignificant.
+    after_block: bool,
+}
+
+fn is_block(name: &str) -> bool {
+    BLOCK_TAGS.contains(&name)
+}
+
+impl Minify {
+    fn is_preformatted(&self) -> bool {
+        self.open
+            .iter()
+            .any(|name| PREFORMATTED_TAGS.contains(&name.as_str()))
+    }
+
+    fn is_foreign(&self) -> bool {
+        self.open.iter().any(|name| name == "svg" || name == "math")
+    }
+
+    /// Writes the pending end tag and whitespace, unless they can be omitted before `next`.
+    fn flush(&mut self, next: Next) {
+        if let Some(end_tag) = self.end_tag.take() {
+            if !end_tag.can_omit(next) {
+                self.out.push_str(&end_tag.text);
+            }
+        }
+
+        if self.space {
+            self.space = false;
+            let is_boundary = match next {
+                Next::Start(name) | Next::End(Some(name)) => is_block(name),
+                Next::End(None) => true,
+                Next::Text | Next::Other => false,
+            };
+
+            if !self.after_block && !is_boundary {
+                self.out.push(' ');
+            }
+        }
+    }
+
+    fn push_name(&mut self, prefix: StrSpan, local: StrSpan, name: &str) {
+        if self.options.lowercase_tag_names {
+            self.out.push_str(name);
+        } else {
+            if !prefix.is_empty() {
+                self.out.push_str(prefix.as_str());
+                self.out.push(':');
+            }
+            self.out.push_str(local.as_str());
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let is_space = |c: char| matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C');
+        let mut words = text.split(is_space).filter(|word| !word.is_empty());
+        let first = match words.next() {
+            Some(first) => first,
+            None => {
+                self.space |= !text.is_empty();
+                return;
+            }
+        };
+
+        self.space |= text.starts_with(is_space);
+        self.flush(Next::Text);
+        self.out.push_str(first);
+        for word in words {
+            self.out.push(' ');
+            self.out.push_str(word);
+        }
+
+        self.space = text.ends_with(is_space);
+        self.after_block = false;
+    }
+
+    fn push_span(&mut self, span: StrSpan) {
+        self.flush(Next::Other);
+        self.out.push_str(span.as_str());
+    }
+}
+
+impl<'a> Handler<'a> for Minify {
+    type Error = Error;
+
+    fn declaration(
+        &mut self,
+        _version: StrSpan<'a>,
+        _encoding: Option<StrSpan<'a>>,
+        _standalone: Option<bool>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_span(span);
+        self.after_block = true;
+        Ok(())
+    }
+
+    fn pi(
+        &mut self,
+        _target: StrSpan<'a>,
+        _content: Option<StrSpan<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_span(span);
+        Ok(())
+    }
+
+    fn doctype(
+        &mut self,
+        _name: StrSpan<'a>,
+        _external_id: Option<ExternalId<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_span(span);
+        self.after_block = true;
+        Ok(())
+    }
+
+    fn doctype_start(
+        &mut self,
+        _name: StrSpan<'a>,
+        _external_id: Option<ExternalId<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_span(span);
+        self.after_block = true;
+        Ok(())
+    }
+
+    fn entity_declaration(
+        &mut self,
+        _name: StrSpan<'a>,
+        _definition: EntityDefinition<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_span(span);
+        Ok(())
+    }
+
+    fn doctype_end(&mut self, span: StrSpan<'a>) -> Result<(), Error> {
+        self.push_span(span);
+        self.after_block = true;
+        Ok(())
+    }
+
+    fn conditional_comment_start(
+        &mut self,
+        _condition: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_span(span);
+        Ok(())
+    }
+
+    fn conditional_comment_end(
+        &mut self,
+        _start: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_span(span);
+        Ok(())
+    }
+
+    fn comment(&mut self, _text: StrSpan<'a>, span: StrSpan<'a>) -> Result<(), Error> {
+        if !self.options.remove_comments {
+            self.push_span(span);
+        }
+        Ok(())
+    }
+
+    fn start_element(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        let name = lowercase_name(prefix, local);
+        self.flush(Next::Start(&name));
+        self.out.push('<');
+        self.push_name(prefix, local, &name);
+        self.after_block = is_block(&name);
+        self.tag = Some(name);
+        Ok(())
+    }
+
+    fn attribute(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        value: Option<StrSpan<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.out.push(' ');
+        if !prefix.is_empty() {
+            self.out.push_str(prefix.as_str());
+            self.out.push(':');
+        }
+        self.out.push_str(local.as_str());
+
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        if self.options.collapse_boolean_attributes {
+            let name = lowercase_name(prefix, local);
+            let is_boolean = BOOLEAN_ATTRIBUTES.contains(&name.as_str())
+                && value.as_str().eq_ignore_ascii_case(&name);
+            if value.is_empty() || is_boolean {
+                return Ok(());
+            }
+        }
+
+        self.out.push('=');
+        let is_unquotable = !value.is_empty()
+            && !value.as_str().contains(|c| {
+                matches!(
+                    c,
+                    ' ' | '\t' | '\n' | '\r' | '\x0C' | '"' | '\'' | '=' | '<' | '>' | '`'
+                )
+            });
+
+        if self.options.remove_attribute_quotes && is_unquotable && !self.is_foreign() {
+            self.out.push_str(value.as_str());
+        } else {
+            // The attribute span ends with the closing quote.
+            let quote = &span.as_str()[span.as_str().len() - 1..];
+            self.out.push_str(quote);
+            self.out.push_str(value.as_str());
+            self.out.push_str(quote);
+        }
+
+        Ok(())
+    }
+
+    fn end_element(&mut self, end: ElementEnd<'a>, _span: StrSpan<'a>) -> Result<(), Error> {
+        match end {
+            ElementEnd::Open => {
+                self.out.push('>');
+                if let Some(name) = self.tag.take() {
+                    if !VOID_TAGS.contains(&name.as_str()) {
+                        self.open.push(name);
+                    }
+                }
+            }
+            ElementEnd::Empty => {
+                let name = self.tag.take().unwrap_or_default();
+                if self.is_foreign() || name == "svg" || name == "math" {
+                    self.out.push_str("/>");
+                } else if VOID_TAGS.contains(&name.as_str()) {
+                    self.out.push('>');
+                } else {
+                    self.out.push_str("></");
+                    self.out.push_str(&name);
+                    self.out.push('>');
+                }
+            }
+            ElementEnd::Close(prefix, local) => {
+                let name = lowercase_name(prefix, local);
+                if VOID_TAGS.contains(&name.as_str()) {
+                    return Ok(());
+                }
+
+                self.flush(Next::End(Some(&name)));
+                if let Some(idx) = self.open.iter().rposition(|open| *open == name) {
+                    self.open.truncate(idx);
+                }
+
+                let start = self.out.len();
+                self.out.push_str("</");
+                self.push_name(prefix, local, &name);
+                self.out.push('>');
+                self.after_block = is_block(&name);
+
+                if self.options.remove_optional_end_tags {
+                    let text = self.out.split_off(start);
+                    self.end_tag = Some(EndTag {
+                        name,
+                        text,
+                        parent: self.open.last().cloned(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn text(&mut self, text: StrSpan<'a>) -> Result<(), Error> {
+        if self.options.collapse_whitespace && !self.is_preformatted() {
+            self.push_text(text.as_str());
+        } else {
+            self.flush(Next::Text);
+            self.out.push_str(text.as_str());
+            self.after_block = false;
+        }
+
+        Ok(())
+    }
+
+    fn cdata(&mut self, _text: StrSpan<'a>, span: StrSpan<'a>) -> Result<(), Error> {
+        self.push_span(span);
+        self.after_block = false;
+        Ok(())
+    }
+}
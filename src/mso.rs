@@ -0,0 +1,290 @@
+use crate::tags::is_void;
+use crate::{ElementEnd, Error, Profile, StrSpan, Token, Tokenizer, Version};
+
+/// A namespace used by the Microsoft Office generated markup.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MsoNamespace {
+    /// Vector Markup Language, like `<v:roundrect>`.
+    Vml,
+    /// Office, like `<o:p>`.
+    Office,
+    /// Word, like `<w:WordDocument>`.
+    Word,
+    /// Excel, like `<x:ExcelWorkbook>`.
+    Excel,
+    /// Office Math, like `<m:oMath>`.
+    Math,
+}
+
+impl MsoNamespace {
+    /// All the known namespaces.
+    pub const ALL: &'static [MsoNamespace] = &[
+        MsoNamespace::Vml,
+        MsoNamespace::Office,
+        MsoNamespace::Word,
+        MsoNamespace::Excel,
+        MsoNamespace::Math,
+    ];
+
+    /// Returns the namespace URI.
+    pub fn uri(&self) -> &'static str {
+        match *self {
+            MsoNamespace::Vml => "urn:schemas-microsoft-com:vml",
+            MsoNamespace::Office => "urn:schemas-microsoft-com:office:office",
+            MsoNamespace::Word => "urn:schemas-microsoft-com:office:word",
+            MsoNamespace::Excel => "urn:schemas-microsoft-com:office:excel",
+            MsoNamespace::Math => "http://schemas.microsoft.com/office/2004/12/omml",
+        }
+    }
+
+    /// Returns the prefix conventionally bound to the namespace.
+    pub fn prefix(&self) -> &'static str {
+        match *self {
+            MsoNamespace::Vml => "v",
+            MsoNamespace::Office => "o",
+            MsoNamespace::Word => "w",
+            MsoNamespace::Excel => "x",
+            MsoNamespace::Math => "m",
+        }
+    }
+
+    /// Returns a namespace by its URI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::MsoNamespace;
+    ///
+    /// let ns = MsoNamespace::from_uri("urn:schemas-microsoft-com:vml");
+    /// assert_eq!(ns, Some(MsoNamespace::Vml));
+    /// ```
+    pub fn from_uri(uri: &str) -> Option<MsoNamespace> {
+        Self::ALL.iter().find(|ns| ns.uri() == uri).cloned()
+    }
+
+    /// Returns a namespace by its conventional prefix.
+    pub fn from_prefix(prefix: &str) -> Option<MsoNamespace> {
+        Self::ALL.iter().find(|ns| ns.prefix() == prefix).cloned()
+    }
+}
+
+impl Profile<'static> {
+    /// Outlook 2007.
+    pub const OUTLOOK_2007: Profile<'static> =
+        Profile::new(&[("mso", Version::new(12, 0)), ("vml", Version::new(1, 0))]);
+
+    /// Outlook 2010.
+    pub const OUTLOOK_2010: Profile<'static> =
+        Profile::new(&[("mso", Version::new(14, 0)), ("vml", Version::new(1, 0))]);
+
+    /// Outlook 2013.
+    pub const OUTLOOK_2013: Profile<'static> =
+        Profile::new(&[("mso", Version::new(15, 0)), ("vml", Version::new(1, 0))]);
+
+    /// Outlook 2016 and later.
+    pub const OUTLOOK_2016: Profile<'static> =
+        Profile::new(&[("mso", Version::new(16, 0)), ("vml", Version::new(1, 0))]);
+}
+
+/// What [`MsoFilter`] does with VML.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum VmlMode {
+    /// Keeps VML elements and the conditional comment blocks containing them.
+    Keep,
+    /// Removes VML elements with their content,
+    /// and the conditional comment blocks containing them.
+    Strip,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Skip<'a> {
+    None,
+    /// Skips everything up to the end of the conditional comment started at the span.
+    Conditional(StrSpan<'a>),
+    /// Skips an element. Contains the number of the open elements
+    /// and whether the current start tag is a void element, like `<br>`.
+    Element(usize, bool),
+    /// Skips the `<o:p>` start tag.
+    OfficeParagraph,
+}
+
+/// A tokenizer adapter that cleans up Microsoft Office generated markup.
+///
+/// `<o:p>` elements are unwrapped, so only their content is preserved.
+/// VML is processed according to the [`VmlMode`].
+///
+/// Prefixes are matched against the `xmlns:*` declarations bound to the VML and Office
+/// namespaces, falling back to the conventional `v` and `o`.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{MsoFilter, Token, Tokenizer, VmlMode};
+///
+/// let text = "<p>Hi<o:p>!</o:p><!--[if mso]><v:rect/><![endif]--></p>";
+///
+/// let tokens: Vec<_> = MsoFilter::new(Tokenizer::from(text), VmlMode::Strip)
+///     .map(|t| t.unwrap().span().as_str())
+///     .collect();
+/// assert_eq!(tokens, vec!["<p", ">", "Hi", "!", "</p>"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MsoFilter<'a> {
+    tokenizer: Tokenizer<'a>,
+    vml: VmlMode,
+    vml_prefix: &'a str,
+    office_prefix: &'a str,
+    skip: Skip<'a>,
+    /// Where the last conditional comment scanned without VML ends.
+    vml_free_end: usize,
+}
+
+impl<'a> MsoFilter<'a> {
+    /// Constructs a new `MsoFilter`.
+    pub fn new(tokenizer: Tokenizer<'a>, vml: VmlMode) -> Self {
+        MsoFilter {
+            tokenizer,
+            vml,
+            vml_prefix: MsoNamespace::Vml.prefix(),
+            office_prefix: MsoNamespace::Office.prefix(),
+            skip: Skip::None,
+            vml_free_end: 0,
+        }
+    }
+
+    fn is_vml(&self, prefix: StrSpan) -> bool {
+        self.vml == VmlMode::Strip && prefix.as_str() == self.vml_prefix
+    }
+
+    fn is_office_paragraph(&self, prefix: StrSpan, local: StrSpan) -> bool {
+        prefix.as_str() == self.office_prefix && local.as_str() == "p"
+    }
+
+    // Checks that the conditional comment started at `start` contains VML.
+    //
+    // Comments nested in a comment without VML are not scanned again,
+    // so each token is looked ahead at most once.
+    fn conditional_contains_vml(&mut self, start: StrSpan<'a>) -> bool {
+        if start.start() < self.vml_free_end {
+            return false;
+        }
+
+        for token in self.tokenizer.clone() {
+            match token {
+                Ok(Token::ElementStart { prefix, .. }) if self.is_vml(prefix) => return true,
+                Ok(Token::ConditionalCommentEnd { start: s, span }) if s == start => {
+                    self.vml_free_end = span.end();
+                    return false;
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+
+        // The tokenizer stops at the end or at the first error.
+        self.vml_free_end = usize::MAX;
+        false
+    }
+
+    fn declare_namespace(&mut self, prefix: StrSpan<'a>, local: StrSpan<'a>, value: StrSpan) {
+        if prefix.as_str() != "xmlns" {
+            return;
+        }
+
+        match MsoNamespace::from_uri(value.as_str()) {
+            Some(MsoNamespace::Vml) => self.vml_prefix = local.as_str(),
+            Some(MsoNamespace::Office) => self.office_prefix = local.as_str(),
+            _ => {}
+        }
+    }
+
+    // Returns `true` when the token has to be skipped.
+    fn process(&mut self, token: &Token<'a>) -> bool {
+        match self.skip {
+            Skip::Conditional(start) => {
+                if let Token::ConditionalCommentEnd { start: s, .. } = *token {
+                    if s == start {
+                        self.skip = Skip::None;
+                    }
+                }
+
+                return true;
+            }
+            Skip::Element(depth, void) => {
+                if let Token::ElementStart { prefix, local, .. } = *token {
+                    self.skip = Skip::Element(depth, is_void(prefix, local));
+                } else if let Token::ElementEnd { end, .. } = *token {
+                    let depth = match end {
+                        ElementEnd::Open if !void => depth + 1,
+                        ElementEnd::Close(..) => depth.saturating_sub(1),
+                        ElementEnd::Open | ElementEnd::Empty => depth,
+                    };
+
+                    self.skip = if depth == 0 {
+                        Skip::None
+                    } else {
+                        Skip::Element(depth, false)
+                    };
+                }
+
+                return true;
+            }
+            Skip::OfficeParagraph => {
+                if let Token::ElementEnd { .. } = *token {
+                    self.skip = Skip::None;
+                }
+
+                return true;
+            }
+            Skip::None => {}
+        }
+
+        match *token {
+            Token::ConditionalCommentStart { span, .. }
+                if self.vml == VmlMode::Strip && self.conditional_contains_vml(span) =>
+            {
+                self.skip = Skip::Conditional(span);
+                return true;
+            }
+            Token::ElementStart { prefix, local, .. } => {
+                if self.is_vml(prefix) {
+                    self.skip = Skip::Element(0, false);
+                    return true;
+                } else if self.is_office_paragraph(prefix, local) {
+                    self.skip = Skip::OfficeParagraph;
+                    return true;
+                }
+            }
+            Token::Attribute {
+                prefix,
+                local,
+                value: Some(value),
+                ..
+            } => {
+                self.declare_namespace(prefix, local, value);
+            }
+            Token::ElementEnd {
+                end: ElementEnd::Close(prefix, local),
+                ..
+            } => {
+                return self.is_office_paragraph(prefix, local);
+            }
+            _ => {}
+        }
+
+        false
+    }
+}
+
+impl<'a> Iterator for MsoFilter<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.tokenizer.next()? {
+                Ok(ref token) if self.process(token) => {}
+                t => return Some(t),
+            }
+        }
+    }
+}
@@ -0,0 +1,573 @@
+use core::fmt;
+use core::ops::{Deref, Range};
+use std::borrow::ToOwned;
+use std::string::String;
+
+use crate::{ElementEnd, EntityDefinition, ExternalId, StrSpan, Token};
+
+/// An owned string slice.
+///
+/// Like [`StrSpan`], but owns the text, so it can outlive the input XML.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OwnedStrSpan {
+    text: String,
+    start: usize,
+}
+
+impl From<StrSpan<'_>> for OwnedStrSpan {
+    #[inline]
+    fn from(span: StrSpan) -> Self {
+        OwnedStrSpan {
+            text: span.as_str().to_owned(),
+            start: span.start(),
+        }
+    }
+}
+
+impl PartialEq<str> for OwnedStrSpan {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for OwnedStrSpan {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl OwnedStrSpan {
+    /// Returns `true` is self is empty.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns the start position of the span.
+    #[inline]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the end position of the span.
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Returns the range of the span.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end()
+    }
+
+    /// Returns the span as a string slice
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns a borrowed `StrSpan` view of the span.
+    #[inline]
+    pub fn as_span(&self) -> StrSpan<'_> {
+        StrSpan::with_start(&self.text, self.start)
+    }
+}
+
+impl fmt::Debug for OwnedStrSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "OwnedStrSpan({:?} {}..{})",
+            self.as_str(),
+            self.start(),
+            self.end()
+        )
+    }
+}
+
+impl fmt::Display for OwnedStrSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Deref for OwnedStrSpan {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OwnedStrSpan {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.as_span().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OwnedStrSpan {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "StrSpan")]
+        struct Repr {
+            text: String,
+            range: Range<usize>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        if repr.range.end.checked_sub(repr.range.start) != Some(repr.text.len()) {
+            return Err(serde::de::Error::custom(
+                "span range doesn't match the text length",
+            ));
+        }
+
+        Ok(OwnedStrSpan {
+            text: repr.text,
+            start: repr.range.start,
+        })
+    }
+}
+
+/// An owned XML token.
+///
+/// Like [`Token`], but owns all the strings, so it can be stored or sent
+/// to another thread after the input XML is dropped.
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedToken {
+    Declaration {
+        version: OwnedStrSpan,
+        encoding: Option<OwnedStrSpan>,
+        standalone: Option<bool>,
+        span: OwnedStrSpan,
+    },
+    ProcessingInstruction {
+        target: OwnedStrSpan,
+        content: Option<OwnedStrSpan>,
+        span: OwnedStrSpan,
+    },
+    ConditionalCommentStart {
+        condition: OwnedStrSpan,
+        span: OwnedStrSpan,
+    },
+    ConditionalCommentEnd {
+        start: OwnedStrSpan,
+        span: OwnedStrSpan,
+    },
+    Comment {
+        text: OwnedStrSpan,
+        span: OwnedStrSpan,
+    },
+    DtdStart {
+        name: OwnedStrSpan,
+        external_id: Option<OwnedExternalId>,
+        span: OwnedStrSpan,
+    },
+    EmptyDtd {
+        name: OwnedStrSpan,
+        external_id: Option<OwnedExternalId>,
+        span: OwnedStrSpan,
+    },
+    EntityDeclaration {
+        name: OwnedStrSpan,
+        definition: OwnedEntityDefinition,
+        span: OwnedStrSpan,
+    },
+    DtdEnd {
+        span: OwnedStrSpan,
+    },
+    ElementStart {
+        prefix: OwnedStrSpan,
+        local: OwnedStrSpan,
+        span: OwnedStrSpan,
+    },
+    Attribute {
+        prefix: OwnedStrSpan,
+        local: OwnedStrSpan,
+        value: Option<OwnedStrSpan>,
+        span: OwnedStrSpan,
+    },
+    ElementEnd {
+        end: OwnedElementEnd,
+        span: OwnedStrSpan,
+    },
+    Text {
+        text: OwnedStrSpan,
+    },
+    Cdata {
+        text: OwnedStrSpan,
+        span: OwnedStrSpan,
+    },
+}
+
+/// An owned `ElementEnd` token.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedElementEnd {
+    /// Indicates `>`
+    Open,
+    /// Indicates `</name>`
+    Close(OwnedStrSpan, OwnedStrSpan),
+    /// Indicates `/>`
+    Empty,
+}
+
+/// An owned [`ExternalId`].
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedExternalId {
+    System(OwnedStrSpan),
+    Public(OwnedStrSpan, OwnedStrSpan),
+}
+
+/// An owned [`EntityDefinition`].
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedEntityDefinition {
+    EntityValue(OwnedStrSpan),
+    ExternalId(OwnedExternalId),
+}
+
+impl<'a> Token<'a> {
+    /// Returns an owned copy of the token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::Tokenizer;
+    ///
+    /// let text = String::from("<p>text</p>");
+    /// let tokens: Vec<_> = Tokenizer::from(text.as_str())
+    ///     .map(|t| t.unwrap().to_owned())
+    ///     .collect();
+    /// drop(text);
+    ///
+    /// assert_eq!(tokens[2].span().as_str(), "text");
+    /// assert_eq!(tokens[2].span().range(), 3..7);
+    /// ```
+    #[allow(clippy::should_implement_trait)]
+    pub fn to_owned(&self) -> OwnedToken {
+        OwnedToken::from(*self)
+    }
+}
+
+impl From<Token<'_>> for OwnedToken {
+    fn from(token: Token) -> Self {
+        let own = OwnedStrSpan::from;
+        let own_opt = |v: Option<StrSpan>| v.map(OwnedStrSpan::from);
+
+        match token {
+            Token::Declaration {
+                version,
+                encoding,
+                standalone,
+                span,
+            } => OwnedToken::Declaration {
+                version: own(version),
+                encoding: own_opt(encoding),
+                standalone,
+                span: own(span),
+            },
+            Token::ProcessingInstruction {
+                target,
+                content,
+                span,
+            } => OwnedToken::ProcessingInstruction {
+                target: own(target),
+                content: own_opt(content),
+                span: own(span),
+            },
+            Token::ConditionalCommentStart { condition, span } => {
+                OwnedToken::ConditionalCommentStart {
+                    condition: own(condition),
+                    span: own(span),
+                }
+            }
+            Token::ConditionalCommentEnd { start, span } => OwnedToken::ConditionalCommentEnd {
+                start: own(start),
+                span: own(span),
+            },
+            Token::Comment { text, span } => OwnedToken::Comment {
+                text: own(text),
+                span: own(span),
+            },
+            Token::DtdStart {
+                name,
+                external_id,
+                span,
+            } => OwnedToken::DtdStart {
+                name: own(name),
+                external_id: external_id.map(OwnedExternalId::from),
+                span: own(span),
+            },
+            Token::EmptyDtd {
+                name,
+                external_id,
+                span,
+            } => OwnedToken::EmptyDtd {
+                name: own(name),
+                external_id: external_id.map(OwnedExternalId::from),
+                span: own(span),
+            },
+            Token::EntityDeclaration {
+                name,
+                definition,
+                span,
+            } => OwnedToken::EntityDeclaration {
+                name: own(name),
+                definition: OwnedEntityDefinition::from(definition),
+                span: own(span),
+            },
+            Token::DtdEnd { span } => OwnedToken::DtdEnd { span: own(span) },
+            Token::ElementStart {
+                prefix,
+                local,
+                span,
+            } => OwnedToken::ElementStart {
+                prefix: own(prefix),
+                local: own(local),
+                span: own(span),
+            },
+            Token::Attribute {
+                prefix,
+                local,
+                value,
+                span,
+            } => OwnedToken::Attribute {
+                prefix: own(prefix),
+                local: own(local),
+                value: own_opt(value),
+                span: own(span),
+            },
+            Token::ElementEnd { end, span } => OwnedToken::ElementEnd {
+                end: OwnedElementEnd::from(end),
+                span: own(span),
+            },
+            Token::Text { text } => OwnedToken::Text { text: own(text) },
+            Token::Cdata { text, span } => OwnedToken::Cdata {
+                text: own(text),
+                span: own(span),
+            },
+        }
+    }
+}
+
+impl From<ElementEnd<'_>> for OwnedElementEnd {
+    fn from(end: ElementEnd) -> Self {
+        match end {
+            ElementEnd::Open => OwnedElementEnd::Open,
+            ElementEnd::Close(prefix, local) => OwnedElementEnd::Close(prefix.into(), local.into()),
+            ElementEnd::Empty => OwnedElementEnd::Empty,
+        }
+    }
+}
+
+impl From<ExternalId<'_>> for OwnedExternalId {
+    fn from(id: ExternalId) -> Self {
+        match id {
+            ExternalId::System(name) => OwnedExternalId::System(name.into()),
+            ExternalId::Public(name, value) => OwnedExternalId::Public(name.into(), value.into()),
+        }
+    }
+}
+
+impl From<EntityDefinition<'_>> for OwnedEntityDefinition {
+    fn from(definition: EntityDefinition) -> Self {
+        match definition {
+            EntityDefinition::EntityValue(value) => {
+                OwnedEntityDefinition::EntityValue(value.into())
+            }
+            EntityDefinition::ExternalId(id) => OwnedEntityDefinition::ExternalId(id.into()),
+        }
+    }
+}
+
+impl OwnedToken {
+    /// Returns a borrowed [`Token`] view of the token.
+    pub fn as_token(&self) -> Token<'_> {
+        fn span(v: &OwnedStrSpan) -> StrSpan<'_> {
+            v.as_span()
+        }
+
+        fn span_opt(v: &Option<OwnedStrSpan>) -> Option<StrSpan<'_>> {
+            v.as_ref().map(OwnedStrSpan::as_span)
+        }
+
+        match *self {
+            OwnedToken::Declaration {
+                ref version,
+                ref encoding,
+                standalone,
+                span: ref s,
+            } => Token::Declaration {
+                version: span(version),
+                encoding: span_opt(encoding),
+                standalone,
+                span: span(s),
+            },
+            OwnedToken::ProcessingInstruction {
+                ref target,
+                ref content,
+                span: ref s,
+            } => Token::ProcessingInstruction {
+                target: span(target),
+                content: span_opt(content),
+                span: span(s),
+            },
+            OwnedToken::ConditionalCommentStart {
+                ref condition,
+                span: ref s,
+            } => Token::ConditionalCommentStart {
+                condition: span(condition),
+                span: span(s),
+            },
+            OwnedToken::ConditionalCommentEnd {
+                ref start,
+                span: ref s,
+            } => Token::ConditionalCommentEnd {
+                start: span(start),
+                span: span(s),
+            },
+            OwnedToken::Comment {
+                ref text,
+                span: ref s,
+            } => Token::Comment {
+                text: span(text),
+                span: span(s),
+            },
+            OwnedToken::DtdStart {
+                ref name,
+                ref external_id,
+                span: ref s,
+            } => Token::DtdStart {
+                name: span(name),
+                external_id: external_id.as_ref().map(OwnedExternalId::as_external_id),
+                span: span(s),
+            },
+            OwnedToken::EmptyDtd {
+                ref name,
+                ref external_id,
+                span: ref s,
+            } => Token::EmptyDtd {
+                name: span(name),
+                external_id: external_id.as_ref().map(OwnedExternalId::as_external_id),
+                span: span(s),
+            },
+            OwnedToken::EntityDeclaration {
+                ref name,
+                ref definition,
+                span: ref s,
+            } => Token::EntityDeclaration {
+                name: span(name),
+                definition: definition.as_entity_definition(),
+                span: span(s),
+            },
+            OwnedToken::DtdEnd { span: ref s } => Token::DtdEnd { span: span(s) },
+            OwnedToken::ElementStart {
+                ref prefix,
+                ref local,
+                span: ref s,
+            } => Token::ElementStart {
+                prefix: span(prefix),
+                local: span(local),
+                span: span(s),
+            },
+            OwnedToken::Attribute {
+                ref prefix,
+                ref local,
+                ref value,
+                span: ref s,
+            } => Token::Attribute {
+                prefix: span(prefix),
+                local: span(local),
+                value: span_opt(value),
+                span: span(s),
+            },
+            OwnedToken::ElementEnd {
+                ref end,
+                span: ref s,
+            } => Token::ElementEnd {
+                end: end.as_element_end(),
+                span: span(s),
+            },
+            OwnedToken::Text { ref text } => Token::Text { text: span(text) },
+            OwnedToken::Cdata {
+                ref text,
+                span: ref s,
+            } => Token::Cdata {
+                text: span(text),
+                span: span(s),
+            },
+        }
+    }
+
+    /// Returns the [`OwnedStrSpan`] encompassing all of the token.
+    pub fn span(&self) -> &OwnedStrSpan {
+        match *self {
+            OwnedToken::Declaration { ref span, .. } => span,
+            OwnedToken::ProcessingInstruction { ref span, .. } => span,
+            OwnedToken::ConditionalCommentStart { ref span, .. } => span,
+            OwnedToken::ConditionalCommentEnd { ref span, .. } => span,
+            OwnedToken::Comment { ref span, .. } => span,
+            OwnedToken::DtdStart { ref span, .. } => span,
+            OwnedToken::EmptyDtd { ref span, .. } => span,
+            OwnedToken::EntityDeclaration { ref span, .. } => span,
+            OwnedToken::DtdEnd { ref span, .. } => span,
+            OwnedToken::ElementStart { ref span, .. } => span,
+            OwnedToken::Attribute { ref span, .. } => span,
+            OwnedToken::ElementEnd { ref span, .. } => span,
+            OwnedToken::Text { ref text, .. } => text,
+            OwnedToken::Cdata { ref span, .. } => span,
+        }
+    }
+}
+
+impl OwnedElementEnd {
+    /// Returns a borrowed [`ElementEnd`] view.
+    pub fn as_element_end(&self) -> ElementEnd<'_> {
+        match *self {
+            OwnedElementEnd::Open => ElementEnd::Open,
+            OwnedElementEnd::Close(ref prefix, ref local) => {
+                ElementEnd::Close(prefix.as_span(), local.as_span())
+            }
+            OwnedElementEnd::Empty => ElementEnd::Empty,
+        }
+    }
+}
+
+impl OwnedExternalId {
+    /// Returns a borrowed [`ExternalId`] view.
+    pub fn as_external_id(&self) -> ExternalId<'_> {
+        match *self {
+            OwnedExternalId::System(ref name) => ExternalId::System(name.as_span()),
+            OwnedExternalId::Public(ref name, ref value) => {
+                ExternalId::Public(name.as_span(), value.as_span())
+            }
+        }
+    }
+}
+
+impl OwnedEntityDefinition {
+    /// Returns a borrowed [`EntityDefinition`] view.
+    pub fn as_entity_definition(&self) -> EntityDefinition<'_> {
+        match *self {
+            OwnedEntityDefinition::EntityValue(ref value) => {
+                EntityDefinition::EntityValue(value.as_span())
+            }
+            OwnedEntityDefinition::ExternalId(ref id) => {
+                EntityDefinition::ExternalId(id.as_external_id())
+            }
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::{HashMap, HashSet};
+use std::string::String;
+use std::vec::Vec;
+
+use crate::escape::{decode_reference, push_escaped};
+use crate::tags::{lowercase_name, VOID_TAGS};
+use crate::{ElementEnd, Error, Handler, StrSpan, Tokenizer};
+
+/// Elements that are removed together with their content.
+const REMOVED_TAGS: &[&str] = &["script", "style"];
+
+/// Attributes that contain a URL.
+const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "formaction",
+    "href",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+const DEFAULT_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "dd",
+    "del",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+];
+
+const DEFAULT_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("*", &["dir", "lang", "title"]),
+    ("a", &["href", "hreflang"]),
+    ("blockquote", &["cite"]),
+    ("img", &["alt", "height", "src", "width"]),
+    ("ol", &["start"]),
+    ("q", &["cite"]),
+    ("td", &["colspan", "rowspan"]),
+    ("th", &["colspan", "rowspan"]),
+];
+
+const DEFAULT_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// An allowlist-based HTML sanitizer.
+///
+/// Allowed elements and attributes are copied from the input,
+/// while the text is escaped. Everything else is removed:
+///
+/// - Disallowed elements are removed, but their content is kept.
+/// - `script` and `style` elements are removed together with their content.
+/// - Event handler attributes, like `onclick`, are always removed.
+/// - URL attributes, like `href` and `src`, are removed
+///   when they use a scheme that is not allowed, like `javascript:`,
+///   or start with a non-ASCII character.
+/// - Comments, conditional comments with their content, CDATA,
+///   processing instructions and DOCTYPE are removed.
+///
+/// Unclosed elements are closed at the end of the output.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::Sanitizer;
+///
+/// let html = Sanitizer::new()
+///     .sanitize("<p onclick='steal()'>Hi <a href='javascript:steal()'>there</a>\
+///                <script>steal()</script></p>")
+///     .unwrap();
+/// assert_eq!(html, "<p>Hi <a>there</a></p>");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sanitizer {
+    tags: HashSet<String>,
+    attributes: HashMap<String, HashSet<String>>,
+    url_schemes: HashSet<String>,
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        let mut sanitizer = Sanitizer::empty()
+            .allow_tags(DEFAULT_TAGS)
+            .allow_url_schemes(DEFAULT_URL_SCHEMES);
+        for (tag, attributes) in DEFAULT_ATTRIBUTES {
+            sanitizer = sanitizer.allow_attributes(tag, attributes);
+        }
+        sanitizer
+    }
+}
+
+impl Sanitizer {
+    /// Creates a sanitizer that allows a basic set of formatting elements,
+    /// links and images, with `http`, `https` and `mailto` URLs.
+    pub fn new() -> Self {
+        Sanitizer::default()
+    }
+
+    /// Creates a sanitizer that allows nothing but the text.
+    pub fn empty() -> Self {
+        Sanitizer {
+            tags: HashSet::new(),
+            attributes: HashMap::new(),
+            url_schemes: HashSet::new(),
+        }
+    }
+
+    /// Allows the elements with the specified names.
+    ///
+    /// `script` and `style` elements are removed anyway.
+    pub fn allow_tags(mut self, tags: &[&str]) -> Self {
+        self.tags
+            .extend(tags.iter().map(|tag| tag.to_ascii_lowercase()));
+        self
+    }
+
+    /// Allows the attributes with the specified names on the `tag` elements.
+    ///
+    /// Attributes allowed on the `*` tag are allowed on all elements.
+    /// Event handler attributes are removed anyway.
+    pub fn allow_attributes(mut self, tag: &str, attributes: &[&str]) -> Self {
+        self.attributes
+            .entry(tag.to_ascii_lowercase())
+            .or_default()
+            .extend(attributes.iter().map(|name| name.to_ascii_lowercase()));
+        self
+    }
+
+    /// Allows the URL schemes, like `https`, in URL attributes.
+    ///
+    /// Relative URLs are always allowed.
+    pub fn allow_url_schemes(mut self, schemes: &[&str]) -> Self {
+        self.url_schemes
+            .extend(schemes.iter().map(|scheme| scheme.to_ascii_lowercase()));
+        self
+    }
+
+    /// Returns the sanitized `text`.
+    ///
+    /// The `text` can be a document or a fragment, like multiple paragraphs.
+    pub fn sanitize(&self, text: &str) -> Result<String, Error> {
+        let mut handler = Sanitize {
+            sanitizer: self,
+            out: String::with_capacity(text.len()),
+            open: Vec::new(),
+            tag: Tag::Ignored,
+            removed: Vec::new(),
+            conditionals: 0,
+        };
+
+        Tokenizer::from(text)
+            .with_fragment_parsing()
+            .visit(&mut handler)?;
+        while let Some(name) = handler.open.pop() {
+            handler.out.push_str("</");
+            handler.out.push_str(&name);
+            handler.out.push('>');
+        }
+
+        Ok(handler.out)
+    }
+
+    fn is_allowed_attribute(&self, tag: &str, name: &str) -> bool {
+        let allowed = |tag| {
+            self.attributes
+                .get(tag)
+                .is_some_and(|names: &HashSet<String>| names.contains(name))
+        };
+
+        !name.starts_with("on") && (allowed(tag) || allowed("*"))
+    }
+
+    fn is_allowed_url(&self, url: &str) -> bool {
+        match url_scheme(url) {
+            Url::Absolute(scheme) => self.url_schemes.contains(&scheme),
+            Url::Relative => true,
+            Url::Unknown => false,
+        }
+    }
+}
+
+enum Url {
+    /// A URL with its lowercase scheme.
+    Absolute(String),
+    Relative,
+    /// A URL starting with a character that is neither a scheme nor a relative URL one.
+    Unknown,
+}
+
+/// Finds the lowercase scheme of the `url` as a browser would see it.
+///
+/// Character references are decoded, tabs and newlines are removed
+/// and leading control characters and spaces are skipped,
+/// so `&#32;jav&#x09;ascript&colon;` is still `javascript`.
+fn url_scheme(url: &str) -> Url {
+    let mut scheme = String::new();
+    let mut rest = url;
+    loop {
+        // Without a colon it's a relative URL.
+        let c = match rest.chars().next() {
+            Some(c) => c,
+            None => return Url::Relative,
+        };
+        let (c, len) = match c {
+            '&' => decode_reference(rest).unwrap_or(('&', 1)),
+            _ => (c, c.len_utf8()),
+        };
+        rest = &rest[len..];
+
+        match c {
+            '\t' | '\n' | '\r' => {}
+            '\0'..=' ' if scheme.is_empty() => {}
+            ':' if !scheme.is_empty() => return Url::Absolute(scheme),
+            'a'..='z' | 'A'..='Z' => scheme.push(c.to_ascii_lowercase()),
+            '0'..='9' | '+' | '-' | '.' if !scheme.is_empty() => scheme.push(c),
+            _ if !scheme.is_empty() || c.is_ascii_graphic() => return Url::Relative,
+            _ => return Url::Unknown,
+        }
+    }
+}
+
+/// The current element start.
+enum Tag {
+    /// An allowed element with its lowercase name.
+    Kept(String),
+    /// A disallowed element, whose content is kept.
+    Dropped,
+    /// An element that is removed together with its content, or an element inside it,
+    /// with its lowercase name.
+    Removed(String),
+    /// An element inside conditional comments.
+    Ignored,
+}
+
+struct Sanitize<'s> {
+    sanitizer: &'s Sanitizer,
+    out: String,
+    /// Names of the allowed elements that are not closed yet.
+    open: Vec<String>,
+    tag: Tag,
+    /// Names of the removed elements that are not closed yet.
+    removed: Vec<String>,
+    /// Nesting level inside conditional comments.
+    conditionals: usize,
+}
+
+impl Sanitize<'_> {
+    fn is_removing(&self) -> bool {
+        !self.removed.is_empty() || self.conditionals != 0
+    }
+
+    fn close(&mut self, name: &str) {
+        let idx = match self.open.iter().rposition(|open| open == name) {
+            Some(idx) => idx,
+            None => return,
+        };
+
+        for name in self.open.drain(idx..).rev() {
+            self.out.push_str("</");
+            self.out.push_str(&name);
+            self.out.push('>');
+        }
+    }
+}
+
+impl<'a> Handler<'a> for Sanitize<'_> {
+    type Error = Error;
+
+    fn start_element(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        let name = lowercase_name(prefix, local);
+        if !self.removed.is_empty() {
+            self.tag = Tag::Removed(name);
+            return Ok(());
+        }
+        if self.conditionals != 0 {
+            self.tag = Tag::Ignored;
+            return Ok(());
+        }
+
+        self.tag = if REMOVED_TAGS.contains(&name.as_str()) {
+            Tag::Removed(name)
+        } else if self.sanitizer.tags.contains(&name) {
+            self.out.push('<');
+            self.out.push_str(&name);
+            Tag::Kept(name)
+        } else {
+            Tag::Dropped
+        };
+
+        Ok(())
+    }
+
+    fn attribute(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        value: Option<StrSpan<'a>>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        let tag = match self.tag {
+            Tag::Kept(ref tag) => tag,
+            _ => return Ok(()),
+        };
+
+        let name = lowercase_name(prefix, local);
+        if !self.sanitizer.is_allowed_attribute(tag, &name) {
+            return Ok(());
+        }
+
+        if let Some(value) = value {
+            if URL_ATTRIBUTES.contains(&name.as_str())
+                && !self.sanitizer.is_allowed_url(value.as_str())
+            {
+                return Ok(());
+            }
+        }
+
+        self.out.push(' ');
+        self.out.push_str(&name);
+        if let Some(value) = value {
+            self.out.push_str("=\"");
+            push_escaped(&mut self.out, value.as_str(), true);
+            self.out.push('"');
+        }
+
+        Ok(())
+    }
+
+    fn end_element(&mut self, end: ElementEnd<'a>, _span: StrSpan<'a>) -> Result<(), Error> {
+        let tag = core::mem::replace(&mut self.tag, Tag::Ignored);
+        match end {
+            ElementEnd::Open => match tag {
+                Tag::Kept(name) => {
+                    self.out.push('>');
+                    if !VOID_TAGS.contains(&name.as_str()) {
+                        self.open.push(name);
+                    }
+                }
+                Tag::Removed(name) => self.removed.push(name),
+                Tag::Ignored | Tag::Dropped => {}
+            },
+            ElementEnd::Empty => {
+                if let Tag::Kept(name) = tag {
+                    if VOID_TAGS.contains(&name.as_str()) {
+                        self.out.push_str("/>");
+                    } else {
+                        self.out.push_str("></");
+                        self.out.push_str(&name);
+                        self.out.push('>');
+                    }
+                }
+            }
+            ElementEnd::Close(prefix, local) => {
+                let name = lowercase_name(prefix, local);
+                if !self.removed.is_empty() {
+                    // Stray end tags inside a removed element must not end it.
+                    if let Some(idx) = self.removed.iter().rposition(|open| *open == name) {
+                        self.removed.truncate(idx);
+                    }
+                } else if self.conditionals == 0 {
+                    self.close(&name);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn text(&mut self, text: StrSpan<'a>) -> Result<(), Error> {
+        if !self.is_removing() {
+            push_escaped(&mut self.out, text.as_str(), false);
+        }
+
+        Ok(())
+    }
+
+    fn conditional_comment_start(
+        &mut self,
+        _condition: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.conditionals += 1;
+        Ok(())
+    }
+
+    fn conditional_comment_end(
+        &mut self,
+        _start: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.conditionals = self.conditionals.saturating_sub(1);
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+// Byte-level scanning used by the hot loops of the tokenizer.
+//
+// Without the `memchr` feature, the haystack is processed a word at a time
+// using the classic "has zero byte" trick, which doesn't require `unsafe`.
+
+const LO: u64 = 0x0101_0101_0101_0101;
+const HI: u64 = 0x8080_8080_8080_8080;
+const WORD: usize = 8;
+
+// Sets the high bit of each byte that is zero.
+//
+// A borrow can set the high bit of a non-zero byte too,
+// but only above a zero one, so the lowest set bit is always exact.
+#[inline]
+fn zero_bytes(v: u64) -> u64 {
+    v.wrapping_sub(LO) & !v & HI
+}
+
+// Sets the high bit of each byte that is less than `n`, with the same guarantees.
+#[inline]
+fn bytes_less_than(v: u64, n: u8) -> u64 {
+    v.wrapping_sub(LO * u64::from(n)) & !v & HI
+}
+
+#[inline]
+fn word(chunk: &[u8]) -> u64 {
+    let mut bytes = [0; WORD];
+    bytes.copy_from_slice(chunk);
+    u64::from_le_bytes(bytes)
+}
+
+// Finds the first byte matching the `check` function.
+//
+// The per-word `mask` function must find at least the first match of each word,
+// its other bits are checked by the `check` function.
+#[inline]
+fn find_by<M, C>(haystack: &[u8], mask: M, check: C) -> Option<usize>
+where
+    M: Fn(u64) -> u64,
+    C: Fn(u8) -> bool,
+{
+    let mut chunks = haystack.chunks_exact(WORD);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let m = mask(word(chunk));
+        if m != 0 {
+            let idx = (m.trailing_zeros() / 8) as usize;
+            if let Some(pos) = chunk[idx..].iter().position(|&c| check(c)) {
+                return Some(offset + idx + pos);
+            }
+        }
+
+        offset += WORD;
+    }
+
+    chunks
+        .remainder()
+        .iter()
+        .position(|&c| check(c))
+        .map(|idx| offset + idx)
+}
+
+/// Returns the index of the first `needle` byte.
+#[cfg(not(feature = "memchr"))]
+#[inline]
+pub(crate) fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    let pattern = LO * u64::from(needle);
+    find_by(haystack, |v| zero_bytes(v ^ pattern), |c| c == needle)
+}
+
+/// Returns the index of the first `needle` byte.
+#[cfg(feature = "memchr")]
+#[inline]
+pub(crate) fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    memchr::memchr(needle, haystack)
+}
+
+/// Returns the index of the first `needle` occurrence.
+///
+/// Looks for the last byte of the `needle` first, since all the terminators
+/// we're looking for, like `-->`, end with a rare `>`.
+#[cfg(not(feature = "memchr"))]
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let (&last, head) = needle.split_last()?;
+    let mut from = head.len();
+    while from < haystack.len() {
+        let end = from + find_byte(&haystack[from..], last)?;
+        let start = end - head.len();
+        if &haystack[start..end] == head {
+            return Some(start);
+        }
+
+        from = end + 1;
+    }
+
+    None
+}
+
+/// Returns the index of the first `needle` occurrence.
+#[cfg(feature = "memchr")]
+#[inline]
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::find(haystack, needle)
+}
+
+/// Returns the index of the first byte that can start a non-XML character.
+///
+/// Those are the control characters, except tabs and line breaks,
+/// and `0xEF`, which starts U+FFFE and U+FFFF as well as many valid characters,
+/// so matches must be checked by the caller.
+#[inline]
+pub(crate) fn find_non_xml_char_candidate(haystack: &[u8]) -> Option<usize> {
+    // Tabs and line breaks are filtered out by the check, since they are rare enough.
+    let pattern = LO * 0xEF;
+    find_by(
+        haystack,
+        |v| bytes_less_than(v, 0x20) | zero_bytes(v ^ pattern),
+        |c| (c < 0x20 && !matches!(c, b'\t' | b'\n' | b'\r')) || c == 0xEF,
+    )
+}
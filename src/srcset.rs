@@ -0,0 +1,525 @@
+use crate::StrSpan;
+
+/// Length units allowed in a `sizes` attribute.
+const LENGTH_UNITS: &[&str] = &[
+    "cap", "ch", "cm", "cqb", "cqh", "cqi", "cqmax", "cqmin", "cqw", "dvb", "dvh", "dvi", "dvmax",
+    "dvmin", "dvw", "em", "ex", "ic", "in", "lh", "lvb", "lvh", "lvi", "lvmax", "lvmin", "lvw",
+    "mm", "pc", "pt", "px", "q", "rcap", "rch", "rem", "rex", "ric", "rlh", "svb", "svh", "svi",
+    "svmax", "svmin", "svw", "vb", "vh", "vi", "vmax", "vmin", "vw",
+];
+
+/// Math functions allowed in a `sizes` attribute.
+const LENGTH_FUNCTIONS: &[&str] = &["calc", "clamp", "max", "min"];
+
+fn is_space(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')
+}
+
+/// Returns the span without the surrounding whitespace.
+fn trim(span: StrSpan) -> StrSpan {
+    let bytes = span.as_str().as_bytes();
+    let start = bytes
+        .iter()
+        .position(|c| !is_space(*c))
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|c| !is_space(*c))
+        .map_or(start, |idx| idx + 1);
+    span.subspan(start, end)
+}
+
+/// Parses a valid non-negative integer, saturating on overflow.
+fn parse_integer(text: &str) -> Option<u32> {
+    if text.is_empty() || !text.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let value = text.bytes().fold(0u32, |value, c| {
+        value.saturating_mul(10).saturating_add(u32::from(c - b'0'))
+    });
+    Some(value)
+}
+
+/// Returns the length of the digits at the start of `bytes`.
+fn digits(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|c| c.is_ascii_digit()).count()
+}
+
+/// Returns the length of the number at the start of `text`,
+/// like `-1.5e3`, with an optional `+` sign in CSS.
+fn number_len(text: &str, css: bool) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut idx = match bytes.first() {
+        Some(b'-') => 1,
+        Some(b'+') if css => 1,
+        _ => 0,
+    };
+
+    let int = digits(&bytes[idx..]);
+    idx += int;
+    let mut fraction = 0;
+    if bytes.get(idx) == Some(&b'.') {
+        fraction = digits(&bytes[idx + 1..]);
+        if fraction != 0 {
+            idx += 1 + fraction;
+        }
+    }
+    if int == 0 && fraction == 0 {
+        return None;
+    }
+
+    if matches!(bytes.get(idx), Some(b'e') | Some(b'E')) {
+        let sign = matches!(bytes.get(idx + 1), Some(b'+') | Some(b'-')) as usize;
+        let exponent = digits(&bytes[(idx + 1 + sign).min(bytes.len())..]);
+        if exponent != 0 {
+            idx += 1 + sign + exponent;
+        }
+    }
+    Some(idx)
+}
+
+/// Parses a valid floating-point number.
+fn parse_float(text: &str) -> Option<f64> {
+    match number_len(text, false) {
+        Some(len) if len == text.len() => text.parse().ok(),
+        _ => None,
+    }
+}
+
+/// An image candidate of a `srcset` attribute, like `image.png 2x`.
+///
+/// Returned by [`Srcset`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ImageCandidate<'a> {
+    url: StrSpan<'a>,
+    descriptors: StrSpan<'a>,
+    width: Option<u32>,
+    density: Option<f64>,
+    height: Option<u32>,
+    valid: bool,
+}
+
+impl<'a> ImageCandidate<'a> {
+    /// Returns the image URL, as written in the attribute value.
+    #[inline]
+    pub fn url(&self) -> StrSpan<'a> {
+        self.url
+    }
+
+    /// Returns all the descriptors, like `100w 50h`.
+    #[inline]
+    pub fn descriptors(&self) -> StrSpan<'a> {
+        self.descriptors
+    }
+
+    /// Returns the width descriptor, like `100` for `100w`.
+    #[inline]
+    pub fn width(&self) -> Option<u32> {
+        self.width
+    }
+
+    /// Returns the pixel density descriptor, like `1.5` for `1.5x`.
+    ///
+    /// A candidate without width and density descriptors has an implicit density of 1.
+    #[inline]
+    pub fn density(&self) -> Option<f64> {
+        self.density
+    }
+
+    /// Returns the height descriptor, like `50` for `50h`.
+    ///
+    /// It's reserved for future use, and only valid along with a width.
+    #[inline]
+    pub fn height(&self) -> Option<u32> {
+        self.height
+    }
+
+    /// Checks that the descriptors are valid.
+    ///
+    /// Browsers ignore the invalid candidates.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    fn add_descriptor(&mut self, descriptor: &str) {
+        let (value, kind) = match descriptor.char_indices().last() {
+            Some((idx, _)) => descriptor.split_at(idx),
+            None => ("", ""),
+        };
+        match kind {
+            "w" if self.width.is_none() && self.density.is_none() => {
+                self.width = parse_integer(value).filter(|width| *width != 0);
+                self.valid &= self.width.is_some();
+            }
+            "x" if self.width.is_none() && self.density.is_none() && self.height.is_none() => {
+                self.density = parse_float(value).filter(|density| *density >= 0.0);
+                self.valid &= self.density.is_some();
+            }
+            "h" if self.height.is_none() && self.density.is_none() => {
+                self.height = parse_integer(value).filter(|height| *height != 0);
+                self.valid &= self.height.is_some();
+            }
+            _ => self.valid = false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DescriptorState {
+    InDescriptor,
+    InParens,
+    AfterDescriptor,
+}
+
+/// An iterator over the image candidates of a `srcset` attribute value.
+///
+/// Follows the [HTML parsing rules](https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute),
+/// but returns the invalid candidates too.
+/// All the spans point into the original value, so each URL can be located precisely.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::{Srcset, Token, Tokenizer};
+///
+/// let text = "<img srcset=\"small.jpg 480w, large.jpg 1080w\"/>";
+/// let value = Tokenizer::from(text)
+///     .find_map(|token| match token {
+///         Ok(Token::Attribute { value, .. }) => value,
+///         _ => None,
+///     })
+///     .unwrap();
+///
+/// let candidates: Vec<_> = Srcset::new(value).collect();
+/// assert_eq!(candidates[1].url().as_str(), "large.jpg");
+/// assert_eq!(candidates[1].url().start(), 29);
+/// assert_eq!(candidates[1].width(), Some(1080));
+/// assert!(candidates.iter().all(|candidate| candidate.is_valid()));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Srcset<'a> {
+    value: StrSpan<'a>,
+    pos: usize,
+}
+
+impl<'a> Srcset<'a> {
+    /// Creates an iterator over the candidates of the `srcset` attribute `value`.
+    pub fn new(value: StrSpan<'a>) -> Self {
+        Srcset { value, pos: 0 }
+    }
+}
+
+impl<'a> From<&'a str> for Srcset<'a> {
+    #[inline]
+    fn from(text: &'a str) -> Self {
+        Srcset::new(StrSpan::from(text))
+    }
+}
+
+impl<'a> Iterator for Srcset<'a> {
+    type Item = ImageCandidate<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.value.as_str().as_bytes();
+        let mut pos = self.pos;
+        while pos < bytes.len() && (is_space(bytes[pos]) || bytes[pos] == b',') {
+            pos += 1;
+        }
+        if pos == bytes.len() {
+            self.pos = pos;
+            return None;
+        }
+
+        let url_start = pos;
+        while pos < bytes.len() && !is_space(bytes[pos]) {
+            pos += 1;
+        }
+
+        let mut url_end = pos;
+        while bytes[url_end - 1] == b',' {
+            url_end -= 1;
+        }
+
+        let mut candidate = ImageCandidate {
+            url: self.value.subspan(url_start, url_end),
+            descriptors: self.value.subspan(pos, pos),
+            width: None,
+            density: None,
+            height: None,
+            valid: true,
+        };
+
+        // A URL that ends with a comma has no descriptors.
+        if url_end == pos {
+            let mut state = DescriptorState::InDescriptor;
+            let mut current: Option<usize> = None;
+            let mut first: Option<usize> = None;
+            let mut last = pos;
+            while pos < bytes.len() && is_space(bytes[pos]) {
+                pos += 1;
+            }
+
+            loop {
+                let c = bytes.get(pos).copied();
+                match state {
+                    DescriptorState::InDescriptor => match c {
+                        Some(c) if is_space(c) => {
+                            if let Some(start) = current.take() {
+                                candidate.add_descriptor(&self.value.as_str()[start..pos]);
+                                last = pos;
+                                state = DescriptorState::AfterDescriptor;
+                            }
+                        }
+                        Some(b',') | None => {
+                            if let Some(start) = current.take() {
+                                candidate.add_descriptor(&self.value.as_str()[start..pos]);
+                                last = pos;
+                            }
+                            pos += c.is_some() as usize;
+                            break;
+                        }
+                        Some(c) => {
+                            let start = *current.get_or_insert(pos);
+                            first.get_or_insert(start);
+                            if c == b'(' {
+                                state = DescriptorState::InParens;
+                            }
+                        }
+                    },
+                    DescriptorState::InParens => match c {
+                        Some(b')') => state = DescriptorState::InDescriptor,
+                        Some(_) => {}
+                        None => {
+                            if let Some(start) = current.take() {
+                                candidate.add_descriptor(&self.value.as_str()[start..pos]);
+                                last = pos;
+                            }
+                            break;
+                        }
+                    },
+                    DescriptorState::AfterDescriptor => match c {
+                        Some(c) if is_space(c) => {}
+                        None => break,
+                        Some(_) => {
+                            state = DescriptorState::InDescriptor;
+                            continue;
+                        }
+                    },
+                }
+                pos += 1;
+            }
+
+            if let Some(first) = first {
+                candidate.descriptors = self.value.subspan(first, last);
+            }
+        }
+
+        if candidate.height.is_some() && candidate.width.is_none() {
+            candidate.valid = false;
+        }
+
+        self.pos = pos;
+        Some(candidate)
+    }
+}
+
+/// An entry of a `sizes` attribute, like `(max-width: 600px) 480px`.
+///
+/// Returned by [`Sizes`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SourceSize<'a> {
+    condition: Option<StrSpan<'a>>,
+    size: StrSpan<'a>,
+    valid: bool,
+}
+
+impl<'a> SourceSize<'a> {
+    /// Returns the media condition, like `(max-width: 600px)`.
+    ///
+    /// The condition is not validated.
+    #[inline]
+    pub fn condition(&self) -> Option<StrSpan<'a>> {
+        self.condition
+    }
+
+    /// Returns the image size, like `480px` or `calc(100vw - 2em)`.
+    #[inline]
+    pub fn size(&self) -> StrSpan<'a> {
+        self.size
+    }
+
+    /// Checks that the size is a non-negative length, a math function or `auto`.
+    ///
+    /// Browsers ignore the invalid entries.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
+
+/// Checks that the `text` is a valid source size value.
+fn is_valid_size(text: &str) -> bool {
+    if text.eq_ignore_ascii_case("auto") {
+        return true;
+    }
+
+    if let Some(idx) = text.find('(') {
+        return text.ends_with(')')
+            && LENGTH_FUNCTIONS
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&text[..idx]));
+    }
+
+    let len = match number_len(text, true) {
+        Some(len) => len,
+        None => return false,
+    };
+    let value: f64 = match text[..len].parse() {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+
+    let unit = &text[len..];
+    match unit.is_empty() {
+        true => value == 0.0,
+        false => {
+            value >= 0.0
+                && LENGTH_UNITS
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(unit))
+        }
+    }
+}
+
+/// An iterator over the entries of a `sizes` attribute value.
+///
+/// Follows the [HTML parsing rules](https://html.spec.whatwg.org/multipage/images.html#parse-a-sizes-attribute),
+/// but returns the invalid entries too, and the entries after the one without a condition.
+/// All the spans point into the original value.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::Sizes;
+///
+/// let sizes: Vec<_> = Sizes::from("(max-width: 600px) 480px, calc(100vw - 2em)").collect();
+/// assert_eq!(sizes[0].condition().unwrap().as_str(), "(max-width: 600px)");
+/// assert_eq!(sizes[0].size().as_str(), "480px");
+/// assert_eq!(sizes[1].condition(), None);
+/// assert_eq!(sizes[1].size().as_str(), "calc(100vw - 2em)");
+/// assert_eq!(sizes[1].size().start(), 26);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Sizes<'a> {
+    value: StrSpan<'a>,
+    pos: usize,
+}
+
+impl<'a> Sizes<'a> {
+    /// Creates an iterator over the entries of the `sizes` attribute `value`.
+    pub fn new(value: StrSpan<'a>) -> Self {
+        Sizes { value, pos: 0 }
+    }
+}
+
+impl<'a> From<&'a str> for Sizes<'a> {
+    #[inline]
+    fn from(text: &'a str) -> Self {
+        Sizes::new(StrSpan::from(text))
+    }
+}
+
+impl<'a> Iterator for Sizes<'a> {
+    type Item = SourceSize<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.value.as_str().as_bytes();
+        loop {
+            if self.pos >= bytes.len() {
+                return None;
+            }
+
+            // Split on the commas outside of blocks, strings and comments.
+            let start = self.pos;
+            let mut depth = 0usize;
+            let mut quote = None;
+            let mut pos = start;
+            while pos < bytes.len() {
+                let c = bytes[pos];
+                match quote {
+                    Some(_) if c == b'\\' => pos += 1,
+                    Some(q) if c == q => quote = None,
+                    Some(_) => {}
+                    None => match c {
+                        b'"' | b'\'' => quote = Some(c),
+                        b'(' | b'[' | b'{' => depth += 1,
+                        b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+                        b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                            pos = match self.value.as_str()[pos + 2..].find("*/") {
+                                Some(len) => pos + 3 + len,
+                                None => bytes.len(),
+                            };
+                        }
+                        b',' if depth == 0 => break,
+                        _ => {}
+                    },
+                }
+                pos += 1;
+            }
+
+            let pos = pos.min(bytes.len());
+            self.pos = pos + 1;
+            let entry = trim(self.value.subspan(start, pos));
+            if entry.is_empty() {
+                continue;
+            }
+
+            // The size is the last component value.
+            let text = entry.as_str().as_bytes();
+            let mut size_start = text.len();
+            if text.ends_with(b")") {
+                let mut depth = 0usize;
+                while size_start > 0 {
+                    size_start -= 1;
+                    match text[size_start] {
+                        b')' => depth += 1,
+                        b'(' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                while size_start > 0
+                    && (text[size_start - 1].is_ascii_alphanumeric()
+                        || text[size_start - 1] == b'-')
+                {
+                    size_start -= 1;
+                }
+                // A media condition in parentheses, without a size.
+                if text[size_start] == b'(' && size_start == 0 {
+                    size_start = text.len();
+                }
+            } else {
+                while size_start > 0
+                    && !is_space(text[size_start - 1])
+                    && text[size_start - 1] != b')'
+                {
+                    size_start -= 1;
+                }
+            }
+
+            let size = entry.subspan(size_start, text.len());
+            let condition = trim(entry.subspan(0, size_start));
+            return Some(SourceSize {
+                condition: Some(condition).filter(|condition| !condition.is_empty()),
+                size,
+                valid: is_valid_size(size.as_str()),
+            });
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use core::ops::Range;
 use core::{char, cmp, str};
 
-use crate::{StrSpan, StreamError, TextPos, XmlByteExt, XmlCharExt};
+use crate::scan;
+#[cfg(feature = "std")]
+use crate::LineIndex;
+use crate::{ColumnUnit, StrSpan, StreamError, TextPos, XmlByteExt, XmlCharExt};
 
 type Result<T> = ::core::result::Result<T, StreamError>;
 
@@ -25,6 +28,9 @@ pub struct Stream<'a> {
     pos: usize,
     end: usize,
     span: StrSpan<'a>,
+    column_unit: ColumnUnit,
+    #[cfg(feature = "std")]
+    line_index: Option<&'a LineIndex<'a>>,
 }
 
 impl<'a> From<&'a str> for Stream<'a> {
@@ -34,6 +40,9 @@ impl<'a> From<&'a str> for Stream<'a> {
             pos: 0,
             end: text.len(),
             span: text.into(),
+            column_unit: ColumnUnit::Chars,
+            #[cfg(feature = "std")]
+            line_index: None,
         }
     }
 }
@@ -45,6 +54,9 @@ impl<'a> From<StrSpan<'a>> for Stream<'a> {
             pos: 0,
             end: span.as_str().len(),
             span,
+            column_unit: ColumnUnit::Chars,
+            #[cfg(feature = "std")]
+            line_index: None,
         }
     }
 }
@@ -57,6 +69,9 @@ impl<'a> Stream<'a> {
             pos: fragment.start,
             end: fragment.end,
             span: text.into(),
+            column_unit: ColumnUnit::Chars,
+            #[cfg(feature = "std")]
+            line_index: None,
         }
     }
 
@@ -66,6 +81,29 @@ impl<'a> Stream<'a> {
         self.span
     }
 
+    /// Returns the unit used to count columns in generated positions.
+    #[inline]
+    pub fn column_unit(&self) -> ColumnUnit {
+        self.column_unit
+    }
+
+    /// Sets the unit used to count columns in generated positions.
+    ///
+    /// Columns are counted in characters by default.
+    #[inline]
+    pub fn set_column_unit(&mut self, unit: ColumnUnit) {
+        self.column_unit = unit;
+    }
+
+    /// Sets the index used to generate positions, instead of rescanning the text.
+    ///
+    /// The index must be built from the stream text, see [`Stream::span`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn set_line_index(&mut self, index: &'a LineIndex<'a>) {
+        self.line_index = Some(index);
+    }
+
     /// Returns current position.
     #[inline]
     pub fn pos(&self) -> usize {
@@ -104,7 +142,7 @@ impl<'a> Stream<'a> {
     #[inline]
     pub fn curr_byte(&self) -> Result<u8> {
         if self.at_end() {
-            return Err(StreamError::UnexpectedEndOfStream);
+            return Err(StreamError::UnexpectedEndOfStream(self.gen_text_pos()));
         }
 
         Ok(self.curr_byte_unchecked())
@@ -128,7 +166,8 @@ impl<'a> Stream<'a> {
     #[inline]
     pub fn next_byte(&self) -> Result<u8> {
         if self.pos + 1 >= self.end {
-            return Err(StreamError::UnexpectedEndOfStream);
+            let pos = self.gen_text_pos_from(self.end);
+            return Err(StreamError::UnexpectedEndOfStream(pos));
         }
 
         Ok(self.span.as_bytes()[self.pos + 1])
@@ -202,12 +241,11 @@ impl<'a> Stream<'a> {
     ///
     /// Unlike `consume_byte()` will not return any errors.
     pub fn try_consume_byte(&mut self, c: u8) -> bool {
-        match self.curr_byte() {
-            Ok(b) if b == c => {
-                self.advance(1);
-                true
-            }
-            _ => false,
+        if !self.at_end() && self.curr_byte_unchecked() == c {
+            self.advance(1);
+            true
+        } else {
+            false
         }
     }
 
@@ -285,6 +323,50 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /// Consumes chars until the `needle` or the end of the stream and returns them.
+    ///
+    /// Like `consume_chars`, but scans bytes instead of decoding chars,
+    /// so it's much faster on long runs of text.
+    ///
+    /// The result can be empty.
+    ///
+    /// # Errors
+    ///
+    /// - `NonXmlChar` - if a non-XML character is found before the `needle`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use htmlparser::Stream;
+    ///
+    /// let mut s = Stream::from("comment --> text");
+    /// assert_eq!(s.consume_until(b"-->").unwrap().as_str(), "comment ");
+    /// assert!(s.starts_with(b"-->"));
+    /// ```
+    pub fn consume_until(&mut self, needle: &[u8]) -> Result<StrSpan<'a>> {
+        let start = self.pos;
+        let bytes = &self.span.as_str().as_bytes()[start..self.end];
+        let len = match needle {
+            [c] => scan::find_byte(bytes, *c),
+            _ => scan::find(bytes, needle),
+        }
+        .unwrap_or(bytes.len());
+
+        let mut from = 0;
+        while let Some(idx) = scan::find_non_xml_char_candidate(&bytes[from..len]) {
+            self.pos = start + from + idx;
+            let c = self.chars().next().unwrap();
+            if !c.is_xml_char() {
+                return Err(StreamError::NonXmlChar(c, self.gen_text_pos()));
+            }
+
+            from += idx + c.len_utf8();
+        }
+
+        self.pos = start + len;
+        Ok(self.slice_back(start))
+    }
+
     #[inline]
     pub(crate) fn chars(&self) -> str::Chars<'a> {
         self.span.as_str()[self.pos..self.end].chars()
@@ -329,7 +411,7 @@ impl<'a> Stream<'a> {
     /// - `InvalidSpace`
     pub fn consume_spaces(&mut self) -> Result<()> {
         if self.at_end() {
-            return Err(StreamError::UnexpectedEndOfStream);
+            return Err(StreamError::UnexpectedEndOfStream(self.gen_text_pos()));
         }
 
         if !self.starts_with_space() {
@@ -370,14 +452,16 @@ impl<'a> Stream<'a> {
     ///
     /// - `InvalidReference`
     pub fn consume_reference(&mut self) -> Result<Reference<'a>> {
+        let start = self.pos();
         self._consume_reference()
-            .map_err(|_| StreamError::InvalidReference)
+            .ok_or_else(|| StreamError::InvalidReference(self.gen_text_pos_from(start)))
     }
 
+    // Doesn't build errors, since their position is expensive to compute.
     #[inline(never)]
-    fn _consume_reference(&mut self) -> Result<Reference<'a>> {
+    fn _consume_reference(&mut self) -> Option<Reference<'a>> {
         if !self.try_consume_byte(b'&') {
-            return Err(StreamError::InvalidReference);
+            return None;
         }
 
         let reference = if self.try_consume_byte(b'#') {
@@ -389,16 +473,22 @@ impl<'a> Stream<'a> {
                 (value, 10)
             };
 
-            let n = u32::from_str_radix(value, radix).map_err(|_| StreamError::InvalidReference)?;
+            let n = u32::from_str_radix(value, radix).ok()?;
 
             let c = char::from_u32(n).unwrap_or('\u{FFFD}');
             if !c.is_xml_char() {
-                return Err(StreamError::InvalidReference);
+                return None;
             }
 
             Reference::Char(c)
         } else {
-            let name = self.consume_name()?;
+            let start = self.pos();
+            if !self.chars().next()?.is_xml_name_start() {
+                return None;
+            }
+
+            self.skip_name().ok()?;
+            let name = self.slice_back(start);
             match name.as_str() {
                 "quot" => Reference::Char('"'),
                 "amp" => Reference::Char('&'),
@@ -409,9 +499,11 @@ impl<'a> Stream<'a> {
             }
         };
 
-        self.consume_byte(b';')?;
+        if !self.try_consume_byte(b';') {
+            return None;
+        }
 
-        Ok(reference)
+        Some(reference)
     }
 
     /// Consumes an XML name and returns it.
@@ -428,7 +520,7 @@ impl<'a> Stream<'a> {
 
         let name = self.slice_back(start);
         if name.is_empty() {
-            return Err(StreamError::InvalidName);
+            return Err(StreamError::InvalidName(self.gen_text_pos()));
         }
 
         Ok(name)
@@ -447,7 +539,7 @@ impl<'a> Stream<'a> {
             if c.is_xml_name_start() {
                 self.advance(c.len_utf8());
             } else {
-                return Err(StreamError::InvalidName);
+                return Err(StreamError::InvalidName(self.gen_text_pos()));
             }
         }
 
@@ -485,7 +577,7 @@ impl<'a> Stream<'a> {
                         self.advance(1);
                     } else {
                         // Multiple `:` is an error.
-                        return Err(StreamError::InvalidName);
+                        return Err(StreamError::InvalidName(self.gen_text_pos()));
                     }
                 } else if b.is_xml_name() {
                     self.advance(1);
@@ -515,18 +607,19 @@ impl<'a> Stream<'a> {
         // Prefix must start with a `NameStartChar`.
         if let Some(c) = prefix.as_str().chars().next() {
             if !c.is_xml_name_start() {
-                return Err(StreamError::InvalidName);
+                return Err(StreamError::InvalidName(self.gen_text_pos_from(start)));
             }
         }
 
         // Local name must start with a `NameStartChar`.
         if let Some(c) = local.as_str().chars().next() {
             if !c.is_xml_name_start() {
-                return Err(StreamError::InvalidName);
+                let pos = splitter.map_or(start, |splitter| splitter + 1);
+                return Err(StreamError::InvalidName(self.gen_text_pos_from(pos)));
             }
         } else {
             // If empty - error.
-            return Err(StreamError::InvalidName);
+            return Err(StreamError::InvalidName(self.gen_text_pos()));
         }
 
         Ok((prefix, local))
@@ -586,15 +679,21 @@ impl<'a> Stream<'a> {
 
     /// Calculates a current absolute position.
     ///
-    /// This operation is very expensive. Use only for errors.
+    /// This operation is very expensive, unless a [`LineIndex`](crate::LineIndex) is set.
+    /// Use only for errors.
     #[inline(never)]
     pub fn gen_text_pos(&self) -> TextPos {
+        #[cfg(feature = "std")]
+        if let Some(index) = self.line_index {
+            return index.text_pos_in(self.pos, self.column_unit);
+        }
+
         let text = self.span.as_str();
         let end = self.pos;
 
         let row = Self::calc_curr_row(text, end);
-        let col = Self::calc_curr_col(text, end);
-        TextPos::new(row, col)
+        let col = Self::calc_curr_col(text, end, self.column_unit);
+        TextPos::new(row, col, end)
     }
 
     /// Calculates an absolute position at `pos`.
@@ -606,8 +705,8 @@ impl<'a> Stream<'a> {
     /// ```
     /// let s = htmlparser::Stream::from("text");
     ///
-    /// assert_eq!(s.gen_text_pos_from(2), htmlparser::TextPos::new(1, 3));
-    /// assert_eq!(s.gen_text_pos_from(9999), htmlparser::TextPos::new(1, 5));
+    /// assert_eq!(s.gen_text_pos_from(2), htmlparser::TextPos::new(1, 3, 2));
+    /// assert_eq!(s.gen_text_pos_from(9999), htmlparser::TextPos::new(1, 5, 4));
     /// ```
     #[inline(never)]
     pub fn gen_text_pos_from(&self, pos: usize) -> TextPos {
@@ -627,16 +726,12 @@ impl<'a> Stream<'a> {
         row
     }
 
-    fn calc_curr_col(text: &str, end: usize) -> u32 {
-        let mut col = 1;
-        for c in text[..end].chars().rev() {
-            if c == '\n' {
-                break;
-            } else {
-                col += 1;
-            }
-        }
+    fn calc_curr_col(text: &str, end: usize, unit: ColumnUnit) -> u32 {
+        let line_start = match text[..end].rfind('\n') {
+            Some(pos) => pos + 1,
+            None => 0,
+        };
 
-        col
+        unit.count(&text[line_start..end]) as u32 + 1
     }
 }
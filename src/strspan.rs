@@ -46,7 +46,7 @@ impl PartialEq<StrSpan<'_>> for &str {
 impl<'a> StrSpan<'a> {
     /// Constructs a new `StrSpan` from substring.
     #[inline]
-    pub(crate) fn from_substr(text: &str, start: usize, end: usize) -> StrSpan {
+    pub(crate) fn from_substr(text: &str, start: usize, end: usize) -> StrSpan<'_> {
         debug_assert!(start <= end);
         StrSpan {
             text: &text[start..end],
@@ -54,6 +54,12 @@ impl<'a> StrSpan<'a> {
         }
     }
 
+    /// Constructs a new `StrSpan` from a text located at `start`.
+    #[inline]
+    pub(crate) fn with_start(text: &'a str, start: usize) -> StrSpan<'a> {
+        StrSpan { text, start }
+    }
+
     /// Returns `true` is self is empty.
     pub fn is_empty(&self) -> bool {
         self.text.is_empty()
@@ -88,6 +94,12 @@ impl<'a> StrSpan<'a> {
     pub(crate) fn slice_region(&self, start: usize, end: usize) -> StrSpan<'a> {
         StrSpan::from_substr(self.text, start, end)
     }
+
+    /// Returns a part of the span, keeping the position in the input.
+    #[inline]
+    pub(crate) fn subspan(&self, start: usize, end: usize) -> StrSpan<'a> {
+        StrSpan::with_start(&self.text[start..end], self.start + start)
+    }
 }
 
 impl fmt::Debug for StrSpan<'_> {
@@ -115,3 +127,18 @@ impl Deref for StrSpan<'_> {
         self.text
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for StrSpan<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("StrSpan", 2)?;
+        state.serialize_field("text", self.as_str())?;
+        state.serialize_field("range", &self.range())?;
+        state.end()
+    }
+}
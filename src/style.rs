@@ -0,0 +1,249 @@
+// A CSS scanner for the text of `style` attributes and elements.
+//
+// Strings, comments, escapes and blocks are skipped as a whole, so the delimiters
+// inside them are ignored. Quotes can also be written as character references,
+// like `&quot;`, which is common in attribute values.
+
+use crate::escape::decode_reference;
+use crate::StrSpan;
+
+fn is_space(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')
+}
+
+/// Returns the span without the surrounding whitespace.
+pub(crate) fn trim(span: StrSpan) -> StrSpan {
+    let bytes = span.as_str().as_bytes();
+    let start = bytes
+        .iter()
+        .position(|c| !is_space(*c))
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|c| !is_space(*c))
+        .map_or(start, |idx| idx + 1);
+    span.subspan(start, end)
+}
+
+/// Returns the quote at `pos`, written as is or as a character reference, with its length.
+pub(crate) fn quote_at(text: &str, pos: usize) -> Option<(char, usize)> {
+    match text.as_bytes().get(pos)? {
+        b'"' => Some(('"', 1)),
+        b'\'' => Some(('\'', 1)),
+        b'&' => decode_reference(&text[pos..]).filter(|(c, _)| *c == '"' || *c == '\''),
+        _ => None,
+    }
+}
+
+/// Returns the position after the comment or the string at `pos`, if any.
+pub(crate) fn skip_atom(text: &str, pos: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if bytes[pos] == b'/' && bytes.get(pos + 1) == Some(&b'*') {
+        let end = text[pos + 2..]
+            .find("*/")
+            .map_or(text.len(), |len| pos + len + 4);
+        return Some(end);
+    }
+
+    let (quote, len) = quote_at(text, pos)?;
+    let mut pos = pos + len;
+    while pos < bytes.len() {
+        if bytes[pos] == b'\\' {
+            pos += 2;
+            continue;
+        }
+        if let Some((c, len)) = quote_at(text, pos) {
+            if c == quote {
+                return Some(pos + len);
+            }
+        }
+        pos += 1;
+    }
+    Some(bytes.len())
+}
+
+/// Returns the position of the first of the `stops` bytes from `pos`,
+/// outside of strings, comments and blocks, or the text length.
+pub(crate) fn scan(text: &str, mut pos: usize, stops: &[u8]) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if depth == 0 && stops.contains(&c) {
+            return pos;
+        }
+
+        match c {
+            b'\\' => pos += 1,
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {
+                if let Some(end) = skip_atom(text, pos) {
+                    pos = end;
+                    continue;
+                }
+            }
+        }
+        pos += 1;
+    }
+    bytes.len()
+}
+
+/// Returns the position after the whitespace, comments and `extra` bytes from `pos`.
+pub(crate) fn skip_space(text: &str, mut pos: usize, extra: &[u8]) -> usize {
+    let bytes = text.as_bytes();
+    while pos < bytes.len() {
+        if is_space(bytes[pos]) || extra.contains(&bytes[pos]) {
+            pos += 1;
+        } else if bytes[pos] == b'/' && bytes.get(pos + 1) == Some(&b'*') {
+            pos = skip_atom(text, pos).unwrap_or(bytes.len());
+        } else {
+            break;
+        }
+    }
+    pos
+}
+
+/// A declaration of a CSS declaration list, like `color: red !important`.
+///
+/// Returned by [`StyleDeclarations`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct StyleDeclaration<'a> {
+    property: StrSpan<'a>,
+    value: StrSpan<'a>,
+    important: bool,
+    span: StrSpan<'a>,
+}
+
+impl<'a> StyleDeclaration<'a> {
+    /// Returns the property name, as written in the input.
+    ///
+    /// Property names are case-insensitive, except for custom properties like `--main-color`.
+    #[inline]
+    pub fn property(&self) -> StrSpan<'a> {
+        self.property
+    }
+
+    /// Returns the value, without the surrounding whitespace and the `!important` flag.
+    #[inline]
+    pub fn value(&self) -> StrSpan<'a> {
+        self.value
+    }
+
+    /// Checks that the declaration ends with `!important`.
+    #[inline]
+    pub fn is_important(&self) -> bool {
+        self.important
+    }
+
+    /// Returns the whole declaration, without the trailing semicolon.
+    #[inline]
+    pub fn span(&self) -> StrSpan<'a> {
+        self.span
+    }
+}
+
+/// An iterator over the declarations of a CSS declaration list,
+/// like the value of a `style` attribute.
+///
+/// Strings, comments, escapes and blocks are handled, so a semicolon inside them
+/// doesn't end a declaration. Quotes written as character references,
+/// like `&quot;`, are recognized too.
+/// Declarations without a colon or a property name are skipped, like browsers do,
+/// but the property names and values are not validated otherwise.
+///
+/// All the spans point into the original value, so a single property can be edited in place.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::StyleDeclarations;
+///
+/// let style = "color:red; font-family: &quot;A;B&quot;, serif ;font-size: 12px !important";
+/// let declarations: Vec<_> = StyleDeclarations::from(style)
+///     .map(|d| (d.property().as_str(), d.value().as_str(), d.is_important()))
+///     .collect();
+/// assert_eq!(declarations, vec![
+///     ("color", "red", false),
+///     ("font-family", "&quot;A;B&quot;, serif", false),
+///     ("font-size", "12px", true),
+/// ]);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct StyleDeclarations<'a> {
+    value: StrSpan<'a>,
+    pos: usize,
+}
+
+impl<'a> StyleDeclarations<'a> {
+    /// Creates an iterator over the declarations of the `value`.
+    pub fn new(value: StrSpan<'a>) -> Self {
+        StyleDeclarations { value, pos: 0 }
+    }
+}
+
+impl<'a> From<&'a str> for StyleDeclarations<'a> {
+    #[inline]
+    fn from(text: &'a str) -> Self {
+        StyleDeclarations::new(StrSpan::from(text))
+    }
+}
+
+impl<'a> Iterator for StyleDeclarations<'a> {
+    type Item = StyleDeclaration<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.value.as_str();
+        loop {
+            let start = skip_space(text, self.pos, b";");
+            if start >= text.len() {
+                self.pos = text.len();
+                return None;
+            }
+
+            let end = scan(text, start, b";");
+            self.pos = end + 1;
+
+            let colon = scan(text, start, b":;");
+            if colon >= end {
+                continue;
+            }
+
+            let property = trim(self.value.subspan(start, colon));
+            if property.is_empty() {
+                continue;
+            }
+
+            let value = trim(self.value.subspan(colon + 1, end));
+            let (value, important) = split_important(value);
+            let span = trim(self.value.subspan(start, end));
+            return Some(StyleDeclaration {
+                property,
+                value,
+                important,
+                span,
+            });
+        }
+    }
+}
+
+/// Splits the `!important` flag from the end of the value.
+fn split_important(value: StrSpan) -> (StrSpan, bool) {
+    let text = value.as_str();
+    let len = text.len();
+    let keyword = "important";
+    if len < keyword.len() || !text.is_char_boundary(len - keyword.len()) {
+        return (value, false);
+    }
+
+    let rest = &text[..len - keyword.len()];
+    if !text[rest.len()..].eq_ignore_ascii_case(keyword) {
+        return (value, false);
+    }
+
+    let rest = rest.trim_end_matches(|c: char| c.is_ascii() && is_space(c as u8));
+    match rest.strip_suffix('!') {
+        Some(rest) => (trim(value.subspan(0, rest.len())), true),
+        None => (value, false),
+    }
+}
@@ -0,0 +1,128 @@
+// Element and attribute categories from the HTML standard.
+
+#[cfg(feature = "std")]
+use std::string::String;
+
+use crate::StrSpan;
+
+/// Elements that never have content.
+pub(crate) const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements that are rendered as blocks or don't render at all,
+/// so the whitespace around them is insignificant.
+#[cfg(feature = "std")]
+pub(crate) const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "caption",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "li",
+    "link",
+    "main",
+    "menu",
+    "meta",
+    "nav",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Elements whose text is rendered or processed as is.
+#[cfg(feature = "std")]
+pub(crate) const PREFORMATTED_TAGS: &[&str] = &["pre", "script", "style", "textarea"];
+
+/// Attributes whose presence alone means `true`.
+#[cfg(feature = "std")]
+pub(crate) const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+/// Checks that the element never has content, ignoring the case.
+pub(crate) fn is_void(prefix: StrSpan, local: StrSpan) -> bool {
+    prefix.is_empty()
+        && VOID_TAGS
+            .iter()
+            .any(|tag| tag.eq_ignore_ascii_case(local.as_str()))
+}
+
+/// Returns the lowercase qualified name, like `svg:rect`.
+#[cfg(feature = "std")]
+pub(crate) fn lowercase_name(prefix: StrSpan, local: StrSpan) -> String {
+    let mut name = String::new();
+    if !prefix.is_empty() {
+        name.push_str(prefix.as_str());
+        name.push(':');
+    }
+    name.push_str(local.as_str());
+    name.make_ascii_lowercase();
+    name
+}
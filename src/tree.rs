@@ -0,0 +1,376 @@
+// A lightweight element tree, for the tools that need more than a token lookahead.
+//
+// Unlike a browser, the tree builder doesn't fix the structure:
+// elements without an end tag contain all the following siblings
+// and stray end tags are kept as is.
+
+use core::ops::Range;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::tags::{lowercase_name, BLOCK_TAGS, VOID_TAGS};
+use crate::{ElementEnd, EntityDefinition, Error, ExternalId, Handler, StrSpan, Tokenizer};
+
+#[derive(Clone, Copy)]
+pub(crate) struct Attribute<'a> {
+    pub name: &'a str,
+    pub value: Option<&'a str>,
+    /// The quote character, as written in the input.
+    pub quote: &'a str,
+    /// The whole attribute.
+    pub span: StrSpan<'a>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum End<'a> {
+    /// A void element without an end tag, like `<br>`.
+    Void,
+    /// A self-closing tag, like `<br/>`.
+    Empty,
+    /// An end tag with the element name.
+    Close(&'a str),
+    /// An element without an end tag before the end of the document.
+    Unclosed,
+}
+
+pub(crate) struct Element<'a> {
+    /// The qualified name, as written in the input.
+    pub name: &'a str,
+    pub lowercase: String,
+    pub attributes: Vec<Attribute<'a>>,
+    pub end: End<'a>,
+    pub children: Vec<Node<'a>>,
+    /// The content of a raw text element, which has no children.
+    pub raw: Option<&'a str>,
+    /// The whole element, from the start tag to the end tag.
+    pub span: Range<usize>,
+    /// Whether the element is a block or contains blocks.
+    pub block: bool,
+    /// Where the start tag ends, and the content starts.
+    pub tag_end: usize,
+}
+
+impl<'a> Element<'a> {
+    /// Returns the value of the attribute with the `name`, ignoring the case.
+    ///
+    /// Returns `Some("")` for an attribute without a value.
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.name.eq_ignore_ascii_case(name))
+            .map(|attr| attr.value.unwrap_or(""))
+    }
+}
+
+pub(crate) enum Node<'a> {
+    Element(Element<'a>),
+    Text(StrSpan<'a>),
+    /// Inline markup copied as is, like comments.
+    Inline(StrSpan<'a>),
+    /// Block markup copied as is, like DOCTYPE and conditional comments.
+    Block(StrSpan<'a>),
+}
+
+impl Node<'_> {
+    pub fn is_block(&self) -> bool {
+        match self {
+            Node::Element(element) => element.block,
+            Node::Block(_) => true,
+            Node::Text(_) | Node::Inline(_) => false,
+        }
+    }
+
+    fn end(&self) -> usize {
+        match self {
+            Node::Element(element) => element.span.end,
+            Node::Text(span) | Node::Inline(span) | Node::Block(span) => span.end(),
+        }
+    }
+}
+
+/// Builds a tree from the `text`.
+///
+/// The content of `raw_tags` elements is not parsed into children.
+pub(crate) fn parse<'a>(text: &'a str, raw_tags: &[&str]) -> Result<Vec<Node<'a>>, Error> {
+    let mut builder = TreeBuilder {
+        text,
+        raw_tags,
+        nodes: Vec::new(),
+        open: Vec::new(),
+        tag: None,
+        raw: 0,
+    };
+
+    Tokenizer::from(text)
+        .with_fragment_parsing()
+        .visit(&mut builder)?;
+    Ok(builder.finish())
+}
+
+/// Returns the qualified name as written in the input.
+fn source_name<'a>(text: &'a str, prefix: StrSpan<'a>, local: StrSpan<'a>) -> &'a str {
+    if prefix.is_empty() {
+        local.as_str()
+    } else {
+        &text[prefix.start()..local.end()]
+    }
+}
+
+struct TreeBuilder<'a, 't> {
+    text: &'a str,
+    raw_tags: &'t [&'t str],
+    nodes: Vec<Node<'a>>,
+    open: Vec<Element<'a>>,
+    /// The current element start.
+    tag: Option<Element<'a>>,
+    /// Nesting level of the same-name elements inside a raw text element.
+    raw: usize,
+}
+
+impl<'a> TreeBuilder<'a, '_> {
+    fn is_raw(&self) -> bool {
+        self.open
+            .last()
+            .is_some_and(|element| element.raw.is_some())
+    }
+
+    fn push(&mut self, node: Node<'a>) {
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.nodes.push(node),
+        }
+    }
+
+    fn push_markup(&mut self, node: Node<'a>) {
+        if !self.is_raw() {
+            self.push(node);
+        }
+    }
+
+    fn close(&mut self, mut element: Element<'a>, end: End<'a>) {
+        element.end = end;
+        element.block = BLOCK_TAGS.contains(&element.lowercase.as_str())
+            || element.children.iter().any(Node::is_block);
+        self.push(Node::Element(element));
+    }
+
+    fn close_unclosed(&mut self, mut element: Element<'a>) {
+        element.span.end = match element.children.last() {
+            Some(child) => child.end(),
+            None => element.tag_end,
+        };
+        self.close(element, End::Unclosed);
+    }
+
+    fn finish(mut self) -> Vec<Node<'a>> {
+        while let Some(mut element) = self.open.pop() {
+            if element.raw.is_some() {
+                // Trailing whitespace would otherwise grow on each formatting.
+                let raw = self.text[element.tag_end..].trim_end();
+                element.raw = Some(raw);
+                element.span.end = element.tag_end + raw.len();
+                self.close(element, End::Unclosed);
+            } else {
+                self.close_unclosed(element);
+            }
+        }
+        self.nodes
+    }
+}
+
+impl<'a> Handler<'a> for TreeBuilder<'a, '_> {
+    type Error = Error;
+
+    fn declaration(
+        &mut self,
+        _version: StrSpan<'a>,
+        _encoding: Option<StrSpan<'a>>,
+        _standalone: Option<bool>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push(Node::Block(span));
+        Ok(())
+    }
+
+    fn pi(
+        &mut self,
+        _target: StrSpan<'a>,
+        _content: Option<StrSpan<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_markup(Node::Block(span));
+        Ok(())
+    }
+
+    fn doctype(
+        &mut self,
+        _name: StrSpan<'a>,
+        _external_id: Option<ExternalId<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push(Node::Block(span));
+        Ok(())
+    }
+
+    fn doctype_start(
+        &mut self,
+        _name: StrSpan<'a>,
+        _external_id: Option<ExternalId<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push(Node::Block(span));
+        Ok(())
+    }
+
+    fn entity_declaration(
+        &mut self,
+        _name: StrSpan<'a>,
+        _definition: EntityDefinition<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push(Node::Block(span));
+        Ok(())
+    }
+
+    fn doctype_end(&mut self, span: StrSpan<'a>) -> Result<(), Error> {
+        self.push(Node::Block(span));
+        Ok(())
+    }
+
+    fn conditional_comment_start(
+        &mut self,
+        _condition: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_markup(Node::Block(span));
+        Ok(())
+    }
+
+    fn conditional_comment_end(
+        &mut self,
+        _start: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.push_markup(Node::Block(span));
+        Ok(())
+    }
+
+    fn comment(&mut self, _text: StrSpan<'a>, span: StrSpan<'a>) -> Result<(), Error> {
+        self.push_markup(Node::Inline(span));
+        Ok(())
+    }
+
+    fn start_element(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        self.tag = Some(Element {
+            name: source_name(self.text, prefix, local),
+            lowercase: lowercase_name(prefix, local),
+            attributes: Vec::new(),
+            end: End::Unclosed,
+            children: Vec::new(),
+            raw: None,
+            span: span.start()..span.end(),
+            block: false,
+            tag_end: span.end(),
+        });
+        Ok(())
+    }
+
+    fn attribute(
+        &mut self,
+        prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        value: Option<StrSpan<'a>>,
+        span: StrSpan<'a>,
+    ) -> Result<(), Error> {
+        if let Some(ref mut tag) = self.tag {
+            // The attribute span ends with the closing quote.
+            let quote = &span.as_str()[span.as_str().len() - 1..];
+            tag.attributes.push(Attribute {
+                name: source_name(self.text, prefix, local),
+                value: value.map(|value| value.as_str()),
+                quote,
+                span,
+            });
+        }
+        Ok(())
+    }
+
+    fn end_element(&mut self, end: ElementEnd<'a>, span: StrSpan<'a>) -> Result<(), Error> {
+        if self.is_raw() {
+            let name = &self.open.last().unwrap().lowercase;
+            match end {
+                ElementEnd::Open if self.tag.take().is_some_and(|tag| tag.lowercase == *name) => {
+                    self.raw += 1;
+                }
+                ElementEnd::Close(prefix, local) if lowercase_name(prefix, local) == *name => {
+                    if self.raw == 0 {
+                        let mut element = self.open.pop().unwrap();
+                        element.raw = Some(&self.text[element.tag_end..span.start()]);
+                        element.span.end = span.end();
+                        self.close(element, End::Close(source_name(self.text, prefix, local)));
+                    } else {
+                        self.raw -= 1;
+                    }
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match end {
+            ElementEnd::Open => {
+                if let Some(mut element) = self.tag.take() {
+                    element.span.end = span.end();
+                    element.tag_end = span.end();
+                    if VOID_TAGS.contains(&element.lowercase.as_str()) {
+                        self.close(element, End::Void);
+                    } else {
+                        if self.raw_tags.contains(&element.lowercase.as_str()) {
+                            element.raw = Some("");
+                        }
+                        self.open.push(element);
+                    }
+                }
+            }
+            ElementEnd::Empty => {
+                if let Some(mut element) = self.tag.take() {
+                    element.span.end = span.end();
+                    element.tag_end = span.end();
+                    self.close(element, End::Empty);
+                }
+            }
+            ElementEnd::Close(prefix, local) => {
+                let name = lowercase_name(prefix, local);
+                match self.open.iter().rposition(|open| open.lowercase == name) {
+                    Some(idx) => {
+                        while self.open.len() > idx + 1 {
+                            let element = self.open.pop().unwrap();
+                            self.close_unclosed(element);
+                        }
+                        let mut element = self.open.pop().unwrap();
+                        element.span.end = span.end();
+                        self.close(element, End::Close(source_name(self.text, prefix, local)));
+                    }
+                    None => self.push(Node::Inline(span)),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn text(&mut self, text: StrSpan<'a>) -> Result<(), Error> {
+        self.push_markup(Node::Text(text));
+        Ok(())
+    }
+
+    fn cdata(&mut self, _text: StrSpan<'a>, span: StrSpan<'a>) -> Result<(), Error> {
+        self.push_markup(Node::Inline(span));
+        Ok(())
+    }
+}
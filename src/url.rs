@@ -0,0 +1,172 @@
+use std::string::String;
+
+/// The parts of a URL reference, as defined in
+/// [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-3).
+struct Reference<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Reference<'a> {
+    fn parse(text: &'a str) -> Self {
+        let (text, fragment) = match text.find('#') {
+            Some(idx) => (&text[..idx], Some(&text[idx + 1..])),
+            None => (text, None),
+        };
+        let (text, query) = match text.find('?') {
+            Some(idx) => (&text[..idx], Some(&text[idx + 1..])),
+            None => (text, None),
+        };
+
+        let scheme_len = text
+            .find(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '+' | '-' | '.'))
+            .filter(|len| {
+                *len != 0
+                    && text.as_bytes()[*len] == b':'
+                    && text.as_bytes()[0].is_ascii_alphabetic()
+            });
+        let (scheme, text) = match scheme_len {
+            Some(len) => (Some(&text[..len]), &text[len + 1..]),
+            None => (None, text),
+        };
+
+        let (authority, path) = match text.strip_prefix("//") {
+            Some(text) => {
+                let len = text.find('/').unwrap_or(text.len());
+                (Some(&text[..len]), &text[len..])
+            }
+            None => (None, text),
+        };
+
+        Reference {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
+}
+
+/// Resolves the `url` against the `base` URL,
+/// as defined in [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-5.2).
+///
+/// Leading and trailing whitespace as well as tabs and newlines are removed first, like browsers do.
+/// The URLs are neither validated nor normalized otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use htmlparser::resolve_url;
+///
+/// let base = "https://example.com/docs/guide/index.html?x=1";
+/// assert_eq!(resolve_url(base, "intro.html"), "https://example.com/docs/guide/intro.html");
+/// assert_eq!(resolve_url(base, "../api/"), "https://example.com/docs/api/");
+/// assert_eq!(resolve_url(base, "/about"), "https://example.com/about");
+/// assert_eq!(resolve_url(base, "//cdn.example.com/a.png"), "https://cdn.example.com/a.png");
+/// assert_eq!(resolve_url(base, "#top"), "https://example.com/docs/guide/index.html?x=1#top");
+/// assert_eq!(resolve_url(base, "mailto:me@example.com"), "mailto:me@example.com");
+/// ```
+pub fn resolve_url(base: &str, url: &str) -> String {
+    let base = clean(base);
+    let url = clean(url);
+    let base = Reference::parse(&base);
+    let reference = Reference::parse(&url);
+
+    let mut out = String::with_capacity(base.path.len() + url.len());
+    let scheme = reference.scheme.or(base.scheme);
+    if let Some(scheme) = scheme {
+        out.push_str(scheme);
+        out.push(':');
+    }
+
+    let query;
+    if reference.scheme.is_some() || reference.authority.is_some() {
+        push_authority(&mut out, reference.authority);
+        push_path(&mut out, reference.path);
+        query = reference.query;
+    } else {
+        push_authority(&mut out, base.authority);
+        if reference.path.is_empty() {
+            out.push_str(base.path);
+            query = reference.query.or(base.query);
+        } else {
+            if reference.path.starts_with('/') {
+                push_path(&mut out, reference.path);
+            } else {
+                // Merge with the base path, up to its last segment.
+                let mut path = String::new();
+                match base.path.rfind('/') {
+                    Some(idx) => path.push_str(&base.path[..idx + 1]),
+                    None if base.authority.is_some() => path.push('/'),
+                    None => {}
+                }
+                path.push_str(reference.path);
+                push_path(&mut out, &path);
+            }
+            query = reference.query;
+        }
+    }
+
+    if let Some(query) = query {
+        out.push('?');
+        out.push_str(query);
+    }
+    if let Some(fragment) = reference.fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
+/// Removes the surrounding whitespace, tabs and newlines.
+fn clean(url: &str) -> String {
+    url.trim_matches(|c| c <= ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect()
+}
+
+fn push_authority(out: &mut String, authority: Option<&str>) {
+    if let Some(authority) = authority {
+        out.push_str("//");
+        out.push_str(authority);
+    }
+}
+
+/// Appends the `path` with the `.` and `..` segments removed,
+/// as defined in [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-5.2.4).
+fn push_path(out: &mut String, path: &str) {
+    let start = out.len();
+    let mut input = path;
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = match input.len() {
+                3 => "/",
+                _ => &input[3..],
+            };
+            let len = out[start..].rfind('/').unwrap_or(0);
+            out.truncate(start + len);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let skip = input.starts_with('/') as usize;
+            let len = input[skip..]
+                .find('/')
+                .map_or(input.len(), |len| len + skip);
+            out.push_str(&input[..len]);
+            input = &input[len..];
+        }
+    }
+}
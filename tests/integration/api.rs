@@ -6,21 +6,21 @@ use htmlparser::*;
 fn text_pos_1() {
     let mut s = Stream::from("text");
     s.advance(2);
-    assert_eq!(s.gen_text_pos(), TextPos::new(1, 3));
+    assert_eq!(s.gen_text_pos(), TextPos::new(1, 3, 2));
 }
 
 #[test]
 fn text_pos_2() {
     let mut s = Stream::from("text\ntext");
     s.advance(6);
-    assert_eq!(s.gen_text_pos(), TextPos::new(2, 2));
+    assert_eq!(s.gen_text_pos(), TextPos::new(2, 2, 6));
 }
 
 #[test]
 fn text_pos_3() {
     let mut s = Stream::from("текст\nтекст");
     s.advance(15);
-    assert_eq!(s.gen_text_pos(), TextPos::new(2, 3));
+    assert_eq!(s.gen_text_pos(), TextPos::new(2, 3, 15));
 }
 
 #[test]
@@ -42,3 +42,202 @@ fn err_size_1() {
 fn err_size_2() {
     assert!(::std::mem::size_of::<StreamError>() <= 64);
 }
+
+#[test]
+fn err_offset_1() {
+    let text = "<p>\n<a b=c>";
+    let err = Tokenizer::from(text).find_map(|t| t.err()).unwrap();
+    assert_eq!(err.offset(), 6);
+    assert_eq!(err.cause().unwrap().offset(), 9);
+    assert_eq!(err.range(), 6..9);
+}
+
+#[test]
+fn err_offset_2() {
+    let text = "<p>текст<!x>";
+    let err = Tokenizer::from(text).find_map(|t| t.err()).unwrap();
+    assert_eq!(err.pos(), TextPos::new(1, 9, 13));
+    assert_eq!(err.range(), 13..13);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn line_index_1() {
+    let text = "<p>\r\n\tтекст\n\n</p>";
+    let index = LineIndex::new(text);
+    let s = Stream::from(text);
+    assert_eq!(index.line_count(), 4);
+    for offset in (0..text.len()).filter(|i| text.is_char_boundary(*i)) {
+        assert_eq!(index.text_pos(offset), s.gen_text_pos_from(offset));
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn line_index_2() {
+    let index = LineIndex::new("");
+    assert_eq!(index.line_count(), 1);
+    assert_eq!(index.text_pos(0), TextPos::new(1, 1, 0));
+    assert_eq!(index.text_pos(10), TextPos::new(1, 1, 0));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn line_index_3() {
+    let index = LineIndex::new("a\nтекст");
+    // Inside the second character.
+    assert_eq!(index.text_pos(5), TextPos::new(2, 2, 4));
+    assert_eq!(index.text_pos(100), TextPos::new(2, 6, 12));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn line_index_utf16() {
+    let index = LineIndex::new("<p>\n😀a😀</p>");
+    assert_eq!(index.text_pos(9), TextPos::new(2, 3, 9));
+    assert_eq!(index.text_pos_utf16(9), TextPos::new(2, 4, 9));
+    assert_eq!(index.text_pos_utf16(13), TextPos::new(2, 6, 13));
+}
+
+#[test]
+fn column_unit_1() {
+    let text = "<p>\n\tт😀<!x></p>";
+    let col = |unit| {
+        Tokenizer::from(text)
+            .with_column_unit(unit)
+            .find_map(|t| t.err())
+            .unwrap()
+            .pos()
+            .col
+    };
+
+    assert_eq!(col(ColumnUnit::Bytes), 8);
+    assert_eq!(col(ColumnUnit::Chars), 4);
+    assert_eq!(col(ColumnUnit::Utf16), 5);
+}
+
+#[test]
+fn column_unit_2() {
+    let mut s = Stream::from("<p a=\"é\" b=c>");
+    s.set_column_unit(ColumnUnit::Bytes);
+    assert_eq!(s.gen_text_pos_from(13), TextPos::new(1, 14, 13));
+    s.set_column_unit(ColumnUnit::Chars);
+    assert_eq!(s.gen_text_pos_from(13), TextPos::new(1, 13, 13));
+}
+
+#[test]
+#[cfg(feature = "unicode-segmentation")]
+fn column_unit_graphemes() {
+    // A family emoji and a letter with a combining accent.
+    let text = "👨\u{200D}👩\u{200D}👧e\u{301}<!x>";
+    let err = Tokenizer::from_fragment(text, 0..text.len())
+        .with_column_unit(ColumnUnit::Graphemes)
+        .find_map(|t| t.err())
+        .unwrap();
+    assert_eq!(err.pos().col, 3);
+    assert_eq!(ColumnUnit::Chars.count(&text[..err.offset()]), 7);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn line_index_units() {
+    let text = "<p>\nт😀e\u{301}</p>";
+    let index = LineIndex::new(text);
+    let mut s = Stream::from(text);
+    for unit in [ColumnUnit::Bytes, ColumnUnit::Chars, ColumnUnit::Utf16] {
+        s.set_column_unit(unit);
+        for offset in (0..text.len()).filter(|i| text.is_char_boundary(*i)) {
+            assert_eq!(index.text_pos_in(offset, unit), s.gen_text_pos_from(offset));
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn line_index_offset_at() {
+    let text = "<p>\n\tт😀e\u{301}</p>\n";
+    let index = LineIndex::new(text);
+    for unit in [ColumnUnit::Bytes, ColumnUnit::Chars, ColumnUnit::Utf16] {
+        for offset in (0..text.len()).filter(|i| text.is_char_boundary(*i)) {
+            let pos = index.text_pos_in(offset, unit);
+            assert_eq!(index.offset_at(pos.row, pos.col, unit), offset);
+        }
+    }
+
+    assert_eq!(index.offset_at(1, 100, ColumnUnit::Chars), 3);
+    assert_eq!(index.offset_at(100, 1, ColumnUnit::Chars), text.len());
+    // Inside a surrogate pair.
+    assert_eq!(index.offset_at(2, 4, ColumnUnit::Utf16), 11);
+}
+
+#[test]
+fn consume_until_1() {
+    // Check every needle position around the word boundaries.
+    for len in 0..40 {
+        for needle in [&b"<"[..], b"?>", b"-->", b"]]>"] {
+            let mut text = "тa-?]>".repeat(len);
+            text.truncate(text.char_indices().nth(len).map_or(text.len(), |(i, _)| i));
+            text.push_str(std::str::from_utf8(needle).unwrap());
+            text.push_str(" tail");
+
+            let mut s = Stream::from(text.as_str());
+            let span = s.consume_until(needle).unwrap();
+            assert_eq!(span.end(), text.len() - needle.len() - " tail".len());
+            assert!(s.starts_with(needle));
+        }
+    }
+}
+
+#[test]
+fn consume_until_2() {
+    let mut s = Stream::from("text without an end");
+    assert_eq!(
+        s.consume_until(b"-->").unwrap().as_str(),
+        "text without an end"
+    );
+    assert!(s.at_end());
+
+    let mut s = Stream::from_substr("<a>text</a>", 3..5);
+    assert_eq!(s.consume_until(b"<").unwrap().as_str(), "te");
+}
+
+#[test]
+fn consume_until_3() {
+    // U+F000 starts with 0xEF, just like U+FFFF, but it's a valid character.
+    let text = "\u{F000}\u{E000}\t\r\nabcdefgh\u{FFFF}<";
+    let mut s = Stream::from(text);
+    assert_eq!(
+        s.consume_until(b"<").unwrap_err(),
+        StreamError::NonXmlChar('\u{FFFF}', TextPos::new(2, 9, 17))
+    );
+    assert_eq!(s.pos(), 17);
+
+    let mut s = Stream::from("abcdefghijklmnop\u{1}<");
+    assert_eq!(
+        s.consume_until(b"<").unwrap_err(),
+        StreamError::NonXmlChar('\u{1}', TextPos::new(1, 17, 16))
+    );
+
+    // Characters after the needle are not checked.
+    let mut s = Stream::from("abc<\u{1}");
+    assert_eq!(s.consume_until(b"<").unwrap().as_str(), "abc");
+}
+
+#[test]
+fn consume_until_4() {
+    // Tabs and line breaks are not reported, even next to control characters.
+    for len in 0..16 {
+        let text = "a".repeat(len) + "\t\r\n\tb\n";
+        let mut s = Stream::from(text.as_str());
+        assert_eq!(s.consume_until(b"<").unwrap().as_str(), text);
+
+        for c in ['\u{0}', '\u{8}', '\u{B}', '\u{C}', '\u{1F}'] {
+            let text = format!("{}\t{}\n<", "a".repeat(len), c);
+            let mut s = Stream::from(text.as_str());
+            assert_eq!(
+                s.consume_until(b"<").unwrap_err(),
+                StreamError::NonXmlChar(c, TextPos::new(1, len as u32 + 2, len + 1))
+            );
+        }
+    }
+}
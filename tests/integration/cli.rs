@@ -0,0 +1,109 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_htmlparser"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn cli_tokens_01() {
+    let output = run(&["tokens"], "<a>\n  text</a>");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "1:1\tElementStart\t\"<a\"\n\
+         1:3\tElementEnd\t\">\"\n\
+         1:4\tText\t\"\\n  text\"\n\
+         2:7\tElementEnd\t\"</a>\"\n"
+    );
+}
+
+#[test]
+fn cli_tokens_json_01() {
+    let output = run(&["tokens", "--json", "-"], "<a/>");
+    assert!(output.status.success());
+    let lines: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["row"], 1);
+    assert_eq!(lines[1]["col"], 3);
+    assert_eq!(lines[1]["token"]["ElementEnd"]["end"], "Empty");
+}
+
+#[test]
+fn cli_tokens_err_01() {
+    let output = run(&["tokens"], "<a x='1' /");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "<stdin>:1:11: error: invalid attribute: unexpected end of stream\n"
+    );
+}
+
+#[test]
+fn cli_check_01() {
+    let output = run(&["check"], "<p><b>text</b></p>");
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn cli_check_02() {
+    let output = run(&["check"], "<p>\n<b x=1>text</b>\n<!-- a -- b -->\n</p>");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "<stdin>:2:6: error: invalid attribute: expected quote mark not '1'\n\
+         <stdin>:3:8: error: invalid comment: '--' is not allowed in comments\n\
+         <stdin>: 2 errors\n"
+    );
+}
+
+#[test]
+fn cli_tree_01() {
+    let output = run(
+        &["tree"],
+        "<html><head><!--[if mso]><o:x/><![endif]--></head><body><p>a<br/></p></body></html>",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "html\n  head\n    [if mso]\n      o:x\n  body\n    p\n      br\n"
+    );
+}
+
+#[test]
+fn cli_tree_02() {
+    let output = run(&["tree"], "<div><p>a<br>b</p><p>c<BR></p></div>");
+    assert_eq!(stdout(&output), "div\n  p\n    br\n  p\n    BR\n");
+}
+
+#[test]
+fn cli_err_01() {
+    let output = run(&["unknown"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: unknown command 'unknown'"));
+}
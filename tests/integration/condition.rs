@@ -18,7 +18,7 @@ fn conditional_comment_in_html() {
             Token::ElementStart("", "style", 41..47),
             Token::ElementEnd(ElementEnd::Open, 47..48),
             Token::ElementEnd(ElementEnd::Close("", "style"), 48..56),
-            Token::ConditionalCommentEnd(56..68),
+            Token::ConditionalCommentEnd(27..41, 56..68),
             Token::ElementEnd(ElementEnd::Close("", "head"), 68..75),
             Token::ElementEnd(ElementEnd::Close("", "html"), 75..82)
         ]
@@ -28,40 +28,282 @@ fn conditional_comment_in_html() {
 test!(
     conditional_comment_start_01,
     "<!--[if IE 8]>",
-    Token::ConditionalCommentStart("if IE 8", 0..14)
+    Token::ConditionalCommentStart("if IE 8", 0..14),
+    Token::Error(
+        "invalid conditional comment at 1:1 cause conditional comment is not closed at 1:1"
+            .to_string()
+    )
 );
 test!(
     conditional_comment_start_02,
     "<!--[if lte IE 7]>",
-    Token::ConditionalCommentStart("if lte IE 7", 0..18)
+    Token::ConditionalCommentStart("if lte IE 7", 0..18),
+    Token::Error(
+        "invalid conditional comment at 1:1 cause conditional comment is not closed at 1:1"
+            .to_string()
+    )
 );
 test!(
     conditional_comment_start_03,
     "<![if !IE]>",
-    Token::ConditionalCommentStart("if !IE", 0..11)
+    Token::ConditionalCommentStart("if !IE", 0..11),
+    Token::Error(
+        "invalid conditional comment at 1:1 cause conditional comment is not closed at 1:1"
+            .to_string()
+    )
 );
 test!(
     conditional_comment_start_04,
     "<!--[if !IE]>-->",
-    Token::ConditionalCommentStart("if !IE", 0..16)
+    Token::ConditionalCommentStart("if !IE", 0..16),
+    Token::Error(
+        "invalid conditional comment at 1:1 cause conditional comment is not closed at 1:1"
+            .to_string()
+    )
 );
 test!(
     conditional_comment_start_05,
     "<!--[if gt IE 6]><!-->",
-    Token::ConditionalCommentStart("if gt IE 6", 0..22)
+    Token::ConditionalCommentStart("if gt IE 6", 0..22),
+    Token::Error(
+        "invalid conditional comment at 1:1 cause conditional comment is not closed at 1:1"
+            .to_string()
+    )
 );
 test!(
     conditional_comment_end_01,
-    "<![endif]-->",
-    Token::ConditionalCommentEnd(0..12)
+    "<![if IE]><![endif]-->",
+    Token::ConditionalCommentStart("if IE", 0..10),
+    Token::ConditionalCommentEnd(0..10, 10..22)
 );
 test!(
     conditional_comment_end_02,
-    "<![endif]>",
-    Token::ConditionalCommentEnd(0..10)
+    "<![if IE]><![endif]>",
+    Token::ConditionalCommentStart("if IE", 0..10),
+    Token::ConditionalCommentEnd(0..10, 10..20)
 );
 test!(
     conditional_comment_end_03,
-    "<!--<![endif]-->",
-    Token::ConditionalCommentEnd(0..16)
+    "<![if IE]><!--<![endif]-->",
+    Token::ConditionalCommentStart("if IE", 0..10),
+    Token::ConditionalCommentEnd(0..10, 10..26)
 );
+
+test!(
+    conditional_comment_nested_01,
+    "<!--[if mso]><![if gt mso 11]><b/><![endif]><![endif]-->",
+    Token::ConditionalCommentStart("if mso", 0..13),
+    Token::ConditionalCommentStart("if gt mso 11", 13..30),
+    Token::ElementStart("", "b", 30..32),
+    Token::ElementEnd(ElementEnd::Empty, 32..34),
+    Token::ConditionalCommentEnd(13..30, 34..44),
+    Token::ConditionalCommentEnd(0..13, 44..56)
+);
+test!(
+    conditional_comment_nested_02,
+    "<![if IE]><![endif]><![if mso]><![endif]>",
+    Token::ConditionalCommentStart("if IE", 0..10),
+    Token::ConditionalCommentEnd(0..10, 10..20),
+    Token::ConditionalCommentStart("if mso", 20..31),
+    Token::ConditionalCommentEnd(20..31, 31..41)
+);
+test!(
+    conditional_comment_err_01,
+    "<p><![endif]--></p>",
+    Token::ElementStart("", "p", 0..2),
+    Token::ElementEnd(ElementEnd::Open, 2..3),
+    Token::Error(
+        "invalid conditional comment at 1:4 cause '<![endif]>' without a matching conditional comment start at 1:4"
+            .to_string()
+    )
+);
+test!(
+    conditional_comment_err_02,
+    "<![if IE]>\n<!--[if mso]><![endif]-->",
+    Token::ConditionalCommentStart("if IE", 0..10),
+    Token::ConditionalCommentStart("if mso", 11..24),
+    Token::ConditionalCommentEnd(11..24, 24..36),
+    Token::Error(
+        "invalid conditional comment at 1:1 cause conditional comment is not closed at 1:1"
+            .to_string()
+    )
+);
+test!(
+    conditional_comment_err_03,
+    "<![if IE]><![if IE]><![if IE]><![if IE]><![if IE]><![if IE]><![if IE]><![if IE]><![if IE]>",
+    Token::ConditionalCommentStart("if IE", 0..10),
+    Token::ConditionalCommentStart("if IE", 10..20),
+    Token::ConditionalCommentStart("if IE", 20..30),
+    Token::ConditionalCommentStart("if IE", 30..40),
+    Token::ConditionalCommentStart("if IE", 40..50),
+    Token::ConditionalCommentStart("if IE", 50..60),
+    Token::ConditionalCommentStart("if IE", 60..70),
+    Token::ConditionalCommentStart("if IE", 70..80),
+    Token::Error(
+        "invalid conditional comment at 1:81 cause conditional comments are nested too deeply at 1:81"
+            .to_string()
+    )
+);
+
+const IE6: html::Profile = html::Profile::new(&[("IE", html::Version::new(6, 0))]);
+const IE55: html::Profile = html::Profile::new(&[("IE", html::Version::new(5, 5))]);
+const IE8: html::Profile = html::Profile::new(&[("IE", html::Version::new(8, 0))]);
+const OUTLOOK_2016: html::Profile = html::Profile::new(&[
+    ("mso", html::Version::new(16, 0)),
+    ("vml", html::Version::new(1, 0)),
+]);
+
+fn evaluate(condition: &str, profile: &html::Profile) -> bool {
+    html::Condition::parse(condition).unwrap().evaluate(profile)
+}
+
+#[test]
+fn condition_feature() {
+    assert!(evaluate("if IE", &IE8));
+    assert!(!evaluate("if IE", &OUTLOOK_2016));
+    assert!(evaluate("if mso", &OUTLOOK_2016));
+    assert!(evaluate("if MSO", &OUTLOOK_2016));
+    assert!(!evaluate("if IE", &html::Profile::MODERN));
+}
+
+#[test]
+fn condition_version() {
+    assert!(evaluate("if IE 8", &IE8));
+    assert!(!evaluate("if IE 7", &IE8));
+    assert!(evaluate("if IE 5", &IE55));
+    assert!(evaluate("if IE 5.5", &IE55));
+    assert!(!evaluate("if IE 5.0", &IE55));
+    assert!(evaluate("if mso 16", &OUTLOOK_2016));
+}
+
+#[test]
+fn condition_comparison() {
+    assert!(evaluate("if lt IE 7", &IE6));
+    assert!(!evaluate("if lt IE 6", &IE6));
+    assert!(evaluate("if lte IE 6", &IE6));
+    assert!(evaluate("if gt IE 6", &IE8));
+    assert!(!evaluate("if gt IE 8", &IE8));
+    assert!(evaluate("if gte IE 8", &IE8));
+    assert!(evaluate("if gte mso 9", &OUTLOOK_2016));
+    assert!(!evaluate("if lt mso 9", &OUTLOOK_2016));
+    assert!(evaluate("if lt IE 5.6", &IE55));
+}
+
+#[test]
+fn condition_operators() {
+    assert!(evaluate("if !IE", &OUTLOOK_2016));
+    assert!(!evaluate("if !IE", &IE8));
+    assert!(evaluate("if (gt IE 5)&(lt IE 7)", &IE6));
+    assert!(!evaluate("if (gt IE 5)&(lt IE 7)", &IE8));
+    assert!(evaluate("if (IE 6) | (IE 8)", &IE8));
+    assert!(evaluate("if mso | IE", &OUTLOOK_2016));
+    assert!(evaluate("if !(IE 7)", &IE8));
+    assert!(evaluate("if true", &IE8));
+    assert!(!evaluate("if false | !IE", &IE8));
+    assert!(evaluate("if IE 6 | IE 8 & !mso", &IE6));
+}
+
+#[test]
+fn condition_err_01() {
+    assert_eq!(
+        html::Condition::parse("IE 8").unwrap_err().to_string(),
+        "expected 'if' at 1:1"
+    );
+}
+
+#[test]
+fn condition_err_02() {
+    assert_eq!(
+        html::Condition::parse("if (IE 8").unwrap_err().to_string(),
+        "unexpected end of stream at 1:9"
+    );
+}
+
+#[test]
+fn condition_err_03() {
+    assert_eq!(
+        html::Condition::parse("if lt IE").unwrap_err().to_string(),
+        "unexpected end of stream at 1:9"
+    );
+}
+
+#[test]
+fn condition_err_04() {
+    assert_eq!(
+        html::Condition::parse("if IE 8 mso")
+            .unwrap_err()
+            .to_string(),
+        "expected '&', '|' not 'm' at 1:9"
+    );
+}
+
+fn filter<'a>(text: &'a str, profile: html::Profile) -> Vec<Token<'a>> {
+    html::ConditionalFilter::new(html::Tokenizer::from(text), profile)
+        .map(to_test_token)
+        .collect()
+}
+
+static EMAIL: &str = "<p><!--[if mso]><b/><![endif]--><!--[if !mso]><!--><i/><!--<![endif]--></p>";
+
+#[test]
+fn conditional_filter_01() {
+    assert_eq!(
+        filter(EMAIL, OUTLOOK_2016),
+        vec![
+            Token::ElementStart("", "p", 0..2),
+            Token::ElementEnd(ElementEnd::Open, 2..3),
+            Token::ElementStart("", "b", 16..18),
+            Token::ElementEnd(ElementEnd::Empty, 18..20),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 71..75),
+        ]
+    );
+}
+
+#[test]
+fn conditional_filter_02() {
+    assert_eq!(
+        filter(EMAIL, html::Profile::MODERN),
+        vec![
+            Token::ElementStart("", "p", 0..2),
+            Token::ElementEnd(ElementEnd::Open, 2..3),
+            Token::ElementStart("", "i", 51..53),
+            Token::ElementEnd(ElementEnd::Empty, 53..55),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 71..75),
+        ]
+    );
+}
+
+#[test]
+fn conditional_filter_03() {
+    // Downlevel-hidden blocks are regular comments for the clients
+    // that don't support conditional comments.
+    assert_eq!(
+        filter(
+            "<p><!--[if !IE]><b/><![endif]--></p>",
+            html::Profile::MODERN
+        ),
+        vec![
+            Token::ElementStart("", "p", 0..2),
+            Token::ElementEnd(ElementEnd::Open, 2..3),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 32..36),
+        ]
+    );
+}
+
+#[test]
+fn conditional_filter_err_01() {
+    assert_eq!(
+        filter("<p><!--[if IE 8 &]><b/><![endif]--></p>", IE8),
+        vec![
+            Token::ElementStart("", "p", 0..2),
+            Token::ElementEnd(ElementEnd::Open, 2..3),
+            Token::Error(
+                "invalid conditional comment at 1:4 cause unexpected end of stream at 1:18"
+                    .to_string()
+            ),
+            Token::ElementStart("", "b", 19..21),
+            Token::ElementEnd(ElementEnd::Empty, 21..23),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 35..39),
+        ]
+    );
+}
@@ -0,0 +1,191 @@
+extern crate htmlparser;
+
+use htmlparser::*;
+
+fn render(text: &str) -> String {
+    let error = Tokenizer::from(text).find_map(|t| t.err()).unwrap();
+    error.diagnostic(text).to_string()
+}
+
+#[test]
+fn unclosed_comment() {
+    assert_eq!(
+        render("<p>\n<!-- text\n</p>"),
+        "error: invalid comment
+ --> 3:5
+  |
+3 | </p>
+  |     ^ expected '-->'
+  |
+  = help: did you forget to close the comment?
+"
+    );
+}
+
+#[test]
+fn missing_space() {
+    assert_eq!(
+        render("<p a='b'c='d'/>"),
+        "error: invalid attribute
+ --> 1:9
+  |
+1 | <p a='b'c='d'/>
+  |         ^ expected space not 'c'
+  |
+  = help: attributes must be separated by spaces
+"
+    );
+}
+
+#[test]
+fn invalid_char_has_no_help() {
+    assert_eq!(
+        render("<br/ >"),
+        "error: invalid attribute
+ --> 1:5
+  |
+1 | <br/ >
+  |    -^ expected '>' not ' '
+"
+    );
+}
+
+#[test]
+fn unknown_token() {
+    assert_eq!(
+        render("<p>\n<!x>"),
+        "error: unknown token
+ --> 2:1
+  |
+2 | <!x>
+  | ^ unknown token
+  |
+  = help: if this is a text, escape '<' as '&lt;' and '&' as '&amp;'
+"
+    );
+}
+
+#[test]
+fn with_path() {
+    let text = "<!-- a -- b -->";
+    let error = Tokenizer::from(text).find_map(|t| t.err()).unwrap();
+    let diagnostic = error.diagnostic(text).with_path("index.html");
+    assert_eq!(diagnostic.error(), error);
+    assert!(diagnostic.to_string().contains(" --> index.html:1:8\n"));
+}
+
+#[test]
+fn keeps_tabs() {
+    let text = "<p>\n\t\t<a b=c>";
+    let rendered = render(text);
+    assert!(rendered.contains("\n  | \t\t  ---^ expected quote mark not 'c'\n"));
+}
+
+#[test]
+fn column_units() {
+    let text = "<p>\n<a é😀=c>";
+    for unit in [ColumnUnit::Bytes, ColumnUnit::Chars, ColumnUnit::Utf16] {
+        let error = Tokenizer::from(text)
+            .with_column_unit(unit)
+            .find_map(|t| t.err())
+            .unwrap();
+        let rendered = error.diagnostic(text).with_column_unit(unit).to_string();
+        assert!(rendered.contains("\n  |   ----^ expected quote mark not 'c'\n"));
+    }
+}
+
+#[test]
+fn wide_line_numbers() {
+    let mut text = String::from("<p>\n");
+    for _ in 0..10 {
+        text.push_str("text\n");
+    }
+    text.push_str("<a b=c>");
+
+    assert!(render(&text).starts_with(
+        "error: invalid attribute
+  --> 12:6
+   |
+12 | <a b=c>
+"
+    ));
+}
+
+#[test]
+fn errors_iter() {
+    let text = "<p a=b>\n<!x>\n<!-- a -- b -->\n</p>";
+    let errors: Vec<_> = Errors::new(text).map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "invalid attribute at 1:3 cause expected quote mark not 'b' at 1:6",
+            "unknown token at 2:1",
+            "invalid comment at 3:1 cause '--' is not allowed in comments at 3:8",
+        ]
+    );
+}
+
+#[test]
+fn errors_iter_resume() {
+    // The conditional comments opened before an error are still open after it.
+    let text = "<!--[if mso]><p a=b></p><![endif]-->";
+    let errors: Vec<_> = Errors::new(text).map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec!["invalid attribute at 1:16 cause expected quote mark not 'b' at 1:19"]
+    );
+
+    let text = "<p>😀<!x><!y></p>";
+    let errors: Vec<_> = Errors::new(text)
+        .with_column_unit(ColumnUnit::Utf16)
+        .map(|e| e.pos().col)
+        .collect();
+    assert_eq!(errors, vec![6, 10]);
+}
+
+#[test]
+fn errors_iter_empty() {
+    assert_eq!(Errors::new("<p></p>").count(), 0);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn errors_iter_line_index() {
+    let text = "<p a=b>\n<!x>\n\t😀 <!-- a -- b -->\n</p>";
+    let index = LineIndex::new(text);
+    let positions = |errors: Errors| -> Vec<_> {
+        errors
+            .map(|e| (e.to_string(), e.offset(), e.cause().map(|c| c.offset())))
+            .collect()
+    };
+    assert_eq!(
+        positions(Errors::new(text).with_line_index(&index)),
+        positions(Errors::new(text))
+    );
+
+    for unit in [ColumnUnit::Bytes, ColumnUnit::Chars, ColumnUnit::Utf16] {
+        let error = |tokenizer: Tokenizer| tokenizer.with_column_unit(unit).find_map(|t| t.err());
+        let text = "<p>\n😀 <a b=c></p>";
+        let index = LineIndex::new(text);
+        assert_eq!(
+            error(Tokenizer::from(text).with_line_index(&index)).map(|e| e.to_string()),
+            error(Tokenizer::from(text)).map(|e| e.to_string())
+        );
+    }
+}
+
+#[test]
+fn zero_row() {
+    let error = Error::UnknownToken(TextPos::new(0, 0, 0));
+    assert_eq!(
+        error.diagnostic("<p>").to_string(),
+        "error: unknown token
+ --> 0:0
+  |
+0 | <p>
+  | ^ unknown token
+  |
+  = help: if this is a text, escape '<' as '&lt;' and '&' as '&amp;'
+"
+    );
+}
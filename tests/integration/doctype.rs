@@ -200,7 +200,7 @@ test!(
     dtd_err_02,
     "<!DOCTYPE s [<!ENTITY % name S YSTEM",
     Token::DtdStart("s", None, 0..13),
-    Token::Error("invalid DTD entity at 1:14 cause invalid ExternalID".to_string())
+    Token::Error("invalid DTD entity at 1:14 cause invalid ExternalID at 1:30".to_string())
 );
 
 test!(
@@ -218,7 +218,7 @@ test!(
     dtd_err_04,
     "<!DOCTYPE s []",
     Token::DtdStart("s", None, 0..13),
-    Token::Error("invalid DTD at 1:14 cause unexpected end of stream".to_string())
+    Token::Error("invalid DTD at 1:14 cause unexpected end of stream at 1:15".to_string())
 );
 
 test!(
@@ -67,19 +67,19 @@ test!(
 test!(
     element_err_01,
     "<>",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
     element_err_02,
     "</",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
     element_err_03,
     "</a",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
@@ -87,31 +87,31 @@ test!(
     "<a x='test' /",
     Token::ElementStart("", "a", 0..2),
     Token::Attribute("", "x", Some("test"), 3..11),
-    Token::Error("invalid attribute at 1:12 cause unexpected end of stream".to_string())
+    Token::Error("invalid attribute at 1:12 cause unexpected end of stream at 1:14".to_string())
 );
 
 test!(
     element_err_05,
     "<<",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
     element_err_06,
     "< a",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
     element_err_07,
     "< ",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
     element_err_08,
     "<&#x9;",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
@@ -142,7 +142,7 @@ test!(
 test!(
     element_err_12,
     "<svg:/>",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:6".to_string())
 );
 
 test!(
@@ -161,31 +161,31 @@ test!(
 test!(
     element_err_14,
     "<-svg/>",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:2".to_string())
 );
 
 test!(
     element_err_15,
     "<svg:-svg/>",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:6".to_string())
 );
 
 test!(
     element_err_16,
     "<svg::svg/>",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:6".to_string())
 );
 
 test!(
     element_err_17,
     "<svg:s:vg/>",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:7".to_string())
 );
 
 test!(
     element_err_18,
     "<::svg/>",
-    Token::Error("invalid element at 1:1 cause invalid name token".to_string())
+    Token::Error("invalid element at 1:1 cause invalid name token at 1:3".to_string())
 );
 
 test!(
@@ -321,3 +321,74 @@ test!(
     Token::Attribute("", "a", Some("v"), 3..8),
     Token::Error("invalid attribute at 1:9 cause expected space not 'b' at 1:9".to_string())
 );
+
+#[test]
+fn next_element_01() {
+    let text = "<!-- c --><svg:rect xlink:href=\"#a\" width='10'\n/>text<p>";
+    let mut tokenizer = html::Tokenizer::from_fragment(text, 0..text.len());
+
+    let tag = tokenizer.next_element().unwrap().unwrap();
+    assert_eq!(tag.prefix().as_str(), "svg");
+    assert_eq!(tag.local().as_str(), "rect");
+    assert_eq!(tag.span().as_str(), &text[10..49]);
+    assert_eq!(tag.span().range(), 10..49);
+    assert!(tag.is_self_closing());
+
+    let attrs: Vec<_> = tag
+        .attributes()
+        .map(|a| {
+            (
+                a.prefix.as_str(),
+                a.local.as_str(),
+                a.value.unwrap().as_str(),
+                a.span.range(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        attrs,
+        vec![("xlink", "href", "#a", 20..35), ("", "width", "10", 36..46)]
+    );
+    assert_eq!(tag.attribute("xlink:href").unwrap().range(), 32..34);
+    assert_eq!(tag.attribute("href"), None);
+
+    // The raw tokens continue after the tag.
+    assert_eq!(
+        to_test_token(tokenizer.next().unwrap()),
+        Token::Text("text", 49..53)
+    );
+}
+
+#[test]
+fn next_element_02() {
+    let mut tokenizer = html::Tokenizer::from("<a>\n<b c=d></b></a>");
+    let tag = tokenizer.next_element().unwrap().unwrap();
+    assert!(!tag.is_self_closing());
+    assert_eq!(tag.attributes().next(), None);
+
+    assert_eq!(
+        tokenizer.next_element().unwrap().unwrap_err().to_string(),
+        "invalid attribute at 2:3 cause expected quote mark not 'd' at 2:6"
+    );
+}
+
+#[test]
+fn next_element_03() {
+    let mut tokenizer = html::Tokenizer::from("<a b>");
+    let tag = tokenizer.next_element().unwrap().unwrap();
+    assert_eq!(tag.attribute("b").unwrap().range(), 4..4);
+    assert!(tokenizer.next_element().is_none());
+
+    let mut tokenizer = html::Tokenizer::from("<p>text</p>");
+    assert_eq!(tokenizer.next_element().unwrap().unwrap().local(), "p");
+    assert!(tokenizer.next_element().is_none());
+}
+
+#[test]
+fn next_element_04() {
+    let mut tokenizer = html::Tokenizer::from_fragment("<a b='c'", 0..8);
+    assert_eq!(
+        tokenizer.next_element().unwrap().unwrap_err().to_string(),
+        "invalid element at 1:1 cause unexpected end of stream at 1:9"
+    );
+}
@@ -0,0 +1,136 @@
+use html::Formatter;
+
+fn format(text: &str) -> String {
+    let formatted = Formatter::new().format(text).unwrap();
+    // Formatting is stable.
+    assert_eq!(Formatter::new().format(&formatted).unwrap(), formatted);
+    formatted
+}
+
+#[test]
+fn format_01() {
+    assert_eq!(
+        format("<html><head><title>Title</title></head>\n<body><div><p>Some <b>bold</b>\n\ntext</p><p>x</p></div></body></html>"),
+        "<html>
+  <head>
+    <title>Title</title>
+  </head>
+  <body>
+    <div>
+      <p>Some <b>bold</b> text</p>
+      <p>x</p>
+    </div>
+  </body>
+</html>
+"
+    );
+}
+
+#[test]
+fn format_02() {
+    // Mixed content keeps the text on separate lines.
+    assert_eq!(
+        format("<div>  before <p>a</p>after <i>it</i> </div>"),
+        "<div>\n  before\n  <p>a</p>\n  after <i>it</i>\n</div>\n"
+    );
+}
+
+#[test]
+fn format_03() {
+    // Preformatted content is untouched.
+    let text = "<div><pre>  a\n    <b>b</b>  </pre>\n<script>\nif (a) {\n  b();\n}\n</script>\
+                <p><textarea>  x\n </textarea></p><style>p { }</style></div>";
+    assert_eq!(
+        format(text),
+        "<div>
+  <pre>  a
+    <b>b</b>  </pre>
+  <script>
+if (a) {
+  b();
+}
+</script>
+  <p><textarea>  x\n </textarea></p>
+  <style>p { }</style>
+</div>
+"
+    );
+}
+
+#[test]
+fn format_04() {
+    let text = "<div><a href=\"https://example.com/a/very/long/path\" class='link link-primary' \
+                title=\"Example\">link</a> <img src='a.png'/></div>";
+    assert_eq!(
+        format(text),
+        "<div>
+  <a
+    href=\"https://example.com/a/very/long/path\"
+    class='link link-primary'
+    title=\"Example\">link</a> <img src='a.png'/>
+</div>
+"
+    );
+    assert_eq!(
+        Formatter::new()
+            .with_indent("\t")
+            .with_max_width(200)
+            .format(&format!("<section>{}</section>", text))
+            .unwrap(),
+        "<section>\n\t<div><a href=\"https://example.com/a/very/long/path\" \
+         class='link link-primary' title=\"Example\">link</a> <img src='a.png'/></div>\n</section>\n"
+    );
+}
+
+#[test]
+fn format_05() {
+    // Comments are inline, while DOCTYPE and conditional comments are blocks.
+    let text = "<!DOCTYPE html>\n<div><!-- a -->text<!--[if mso]><table><tr><td><![endif]-->\
+                <p>b</p><!--[if mso]></td></tr></table><![endif]--></div>";
+    assert_eq!(
+        format(text),
+        "<!DOCTYPE html>
+<div>
+  <!-- a -->text
+  <!--[if mso]>
+  <table>
+    <tr>
+      <td>
+        <![endif]-->
+        <p>b</p>
+        <!--[if mso]>
+      </td>
+    </tr>
+  </table>
+  <![endif]-->
+</div>
+"
+    );
+}
+
+#[test]
+fn format_06() {
+    // Unclosed and stray tags are kept.
+    assert_eq!(
+        format("<ul><li>a<li>b</ul></span><br><hr></hr>"),
+        "<ul>\n  <li>\n    a\n    <li>b\n</ul>\n</span><br>\n<hr>\n</hr>\n"
+    );
+}
+
+#[test]
+fn format_07() {
+    let corpora = [
+        include_str!("../../benches/corpora/wikipedia.html"),
+        include_str!("../../benches/corpora/spa.html"),
+        include_str!("../../benches/corpora/email.html"),
+        include_str!("../../benches/corpora/image.svg"),
+    ];
+    for text in corpora {
+        format(text);
+    }
+}
+
+#[test]
+fn format_08() {
+    assert_eq!(format("<p>a</p><p>b</p>"), "<p>a</p>\n<p>b</p>\n");
+}
@@ -0,0 +1,236 @@
+use html::{ElementEnd, Error, Handler, StrSpan, Tokenizer};
+
+#[derive(Debug, PartialEq)]
+enum Stop {
+    Parse(String),
+    Element(String),
+}
+
+impl From<Error> for Stop {
+    fn from(e: Error) -> Self {
+        Stop::Parse(e.to_string())
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+    stop_at: Option<&'static str>,
+}
+
+impl<'a> Handler<'a> for Recorder {
+    type Error = Stop;
+
+    fn start_element(
+        &mut self,
+        _prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        self.events.push(format!("start {}", local));
+        if self.stop_at == Some(local.as_str()) {
+            return Err(Stop::Element(local.to_string()));
+        }
+        Ok(())
+    }
+
+    fn attribute(
+        &mut self,
+        _prefix: StrSpan<'a>,
+        local: StrSpan<'a>,
+        value: Option<StrSpan<'a>>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        let value = value.map(|v| v.as_str()).unwrap_or_default();
+        self.events.push(format!("attr {}={}", local, value));
+        Ok(())
+    }
+
+    fn end_element(&mut self, end: ElementEnd<'a>, _span: StrSpan<'a>) -> Result<(), Stop> {
+        self.events.push(match end {
+            ElementEnd::Open => "open".to_string(),
+            ElementEnd::Empty => "empty".to_string(),
+            ElementEnd::Close(_, local) => format!("close {}", local),
+        });
+        Ok(())
+    }
+
+    fn text(&mut self, text: StrSpan<'a>) -> Result<(), Stop> {
+        self.events.push(format!("text {}", text));
+        Ok(())
+    }
+
+    fn comment(&mut self, text: StrSpan<'a>, _span: StrSpan<'a>) -> Result<(), Stop> {
+        self.events.push(format!("comment {}", text));
+        Ok(())
+    }
+
+    fn conditional_comment_start(
+        &mut self,
+        condition: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        self.events.push(format!("[{}]", condition));
+        Ok(())
+    }
+
+    fn conditional_comment_end(
+        &mut self,
+        _start: StrSpan<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        self.events.push("endif".to_string());
+        Ok(())
+    }
+
+    fn doctype(
+        &mut self,
+        name: StrSpan<'a>,
+        _external_id: Option<html::ExternalId<'a>>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        self.events.push(format!("doctype {}", name));
+        Ok(())
+    }
+
+    fn doctype_start(
+        &mut self,
+        name: StrSpan<'a>,
+        _external_id: Option<html::ExternalId<'a>>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        self.events.push(format!("doctype start {}", name));
+        Ok(())
+    }
+
+    fn entity_declaration(
+        &mut self,
+        name: StrSpan<'a>,
+        _definition: html::EntityDefinition<'a>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        self.events.push(format!("entity {}", name));
+        Ok(())
+    }
+
+    fn doctype_end(&mut self, _span: StrSpan<'a>) -> Result<(), Stop> {
+        self.events.push("doctype end".to_string());
+        Ok(())
+    }
+
+    fn cdata(&mut self, text: StrSpan<'a>, _span: StrSpan<'a>) -> Result<(), Stop> {
+        self.events.push(format!("cdata {}", text));
+        Ok(())
+    }
+
+    fn pi(
+        &mut self,
+        target: StrSpan<'a>,
+        _content: Option<StrSpan<'a>>,
+        _span: StrSpan<'a>,
+    ) -> Result<(), Stop> {
+        self.events.push(format!("pi {}", target));
+        Ok(())
+    }
+}
+
+#[test]
+fn handler_01() {
+    let text = "<!DOCTYPE html><?php?><p class='a' hidden><!-- c --><![CDATA[d]]>e<br/>\
+                <!--[if mso]><i></i><![endif]--></p>";
+    let mut recorder = Recorder::default();
+    Tokenizer::from(text).visit(&mut recorder).unwrap();
+    assert_eq!(
+        recorder.events,
+        vec![
+            "doctype html",
+            "pi php",
+            "start p",
+            "attr class=a",
+            "attr hidden=",
+            "open",
+            "comment  c ",
+            "cdata d",
+            "text e",
+            "start br",
+            "empty",
+            "[if mso]",
+            "start i",
+            "open",
+            "close i",
+            "endif",
+            "close p",
+        ]
+    );
+}
+
+#[test]
+fn handler_02() {
+    let mut recorder = Recorder {
+        stop_at: Some("b"),
+        ..Recorder::default()
+    };
+    let mut tokenizer = Tokenizer::from("<a><b></b><c/></a>");
+    assert_eq!(
+        tokenizer.visit(&mut recorder),
+        Err(Stop::Element("b".to_string()))
+    );
+    assert_eq!(recorder.events, vec!["start a", "open", "start b"]);
+
+    // Resumes right after the token that stopped the parsing.
+    recorder.stop_at = None;
+    recorder.events.clear();
+    tokenizer.visit(&mut recorder).unwrap();
+    assert_eq!(
+        recorder.events,
+        vec!["open", "close b", "start c", "empty", "close a"]
+    );
+}
+
+#[test]
+fn handler_03() {
+    let mut recorder = Recorder::default();
+    assert_eq!(
+        Tokenizer::from("<a>text</a><").visit(&mut recorder),
+        Err(Stop::Parse("unknown token at 1:12".to_string()))
+    );
+    assert_eq!(
+        recorder.events,
+        vec!["start a", "open", "text text", "close a"]
+    );
+}
+
+struct Lenient(usize);
+
+impl<'a> Handler<'a> for Lenient {
+    type Error = Error;
+
+    fn error(&mut self, _error: Error) -> Result<(), Error> {
+        self.0 += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn handler_04() {
+    let mut handler = Lenient(0);
+    assert!(Tokenizer::from("<a b=c>").visit(&mut handler).is_ok());
+    assert_eq!(handler.0, 1);
+}
+
+#[test]
+fn handler_05() {
+    let text = "<!DOCTYPE svg [<!ENTITY a 'b'>]><svg/>";
+    let mut recorder = Recorder::default();
+    Tokenizer::from(text).visit(&mut recorder).unwrap();
+    assert_eq!(
+        recorder.events,
+        vec![
+            "doctype start svg",
+            "entity a",
+            "doctype end",
+            "start svg",
+            "empty"
+        ]
+    );
+}
@@ -0,0 +1,95 @@
+use html::CssInliner;
+
+fn inline(text: &str) -> String {
+    CssInliner::new().inline(text).unwrap()
+}
+
+#[test]
+fn inline_01() {
+    let text = "<html><head><style>p { color: red } .big { font-size: 20px }</style></head>\
+                <body><p class=\"big\">Hi</p><p>There<br/></p></body></html>";
+    assert_eq!(
+        inline(text),
+        "<html><head></head><body><p class=\"big\" style=\"color: red; font-size: 20px\">Hi</p>\
+         <p style=\"color: red\">There<br/></p></body></html>"
+    );
+}
+
+#[test]
+fn inline_02() {
+    let text = "<div><style>#a { color: red } div p.x { color: blue !important } \
+                p { color: green; margin: 0 }</style>\
+                <p id=\"a\" class=\"x\" style=\"color: black\">a</p><p style='margin: 1px'>b</p></div>";
+    assert_eq!(
+        inline(text),
+        "<div><p id=\"a\" class=\"x\" style=\"margin: 0; color: blue\">a</p>\
+         <p style='color: green; margin: 1px'>b</p></div>"
+    );
+
+    assert_eq!(
+        CssInliner::new()
+            .with_preserve_important(true)
+            .inline(text)
+            .unwrap(),
+        "<div><p id=\"a\" class=\"x\" style=\"margin: 0; color: blue !important\">a</p>\
+         <p style='color: green; margin: 1px'>b</p></div>"
+    );
+}
+
+#[test]
+fn inline_03() {
+    let text = "<div><style>@media (max-width: 600px) { p { color: blue } } \
+                a:hover, b { font-weight: bold } p::first-line { color: red }</style>\
+                <p><a href=\"/\">a</a><b>b</b></p></div>";
+    assert_eq!(
+        inline(text),
+        "<div><style>@media (max-width: 600px) { p { color: blue } }\n\
+         a:hover { font-weight: bold }\np::first-line { color: red }</style>\
+         <p><a href=\"/\">a</a><b style=\"font-weight: bold\">b</b></p></div>"
+    );
+
+    assert_eq!(
+        CssInliner::new()
+            .with_keep_style_elements(true)
+            .inline(text)
+            .unwrap(),
+        "<div><style>@media (max-width: 600px) { p { color: blue } } \
+         a:hover, b { font-weight: bold } p::first-line { color: red }</style>\
+         <p><a href=\"/\">a</a><b style=\"font-weight: bold\">b</b></p></div>"
+    );
+}
+
+#[test]
+fn inline_04() {
+    let text =
+        "<div><style>li:first-child { a: 1 } li + li { b: 2 } ul > li:nth-child(odd) { c: 3 } \
+                [href^='http'] { font-family: \"A\" }</style>\
+                <ul><li><a href=\"https://x\">a</a></li><li/><li>c</li></ul></div>";
+    assert_eq!(
+        inline(text),
+        "<div><ul><li style=\"a: 1; c: 3\"><a href=\"https://x\" style=\"font-family: &quot;A&quot;\">a</a></li>\
+         <li style=\"b: 2\"/><li style=\"b: 2; c: 3\">c</li></ul></div>"
+    );
+}
+
+#[test]
+fn inline_05() {
+    // Print and Outlook only styles are left as is.
+    let text = "<div><style media=\"print\">p { color: red }</style>\
+                <!--[if mso]><style>p { color: red }</style><![endif]-->\
+                <style>p { font-family: 'A' }</style><p style='x: 1'>x</p><title>p</title></div>";
+    assert_eq!(
+        inline(text),
+        "<div><style media=\"print\">p { color: red }</style>\
+         <!--[if mso]><style>p { color: red }</style><![endif]-->\
+         <p style='font-family: &#39;A&#39;; x: 1'>x</p><title>p</title></div>"
+    );
+}
+
+#[test]
+fn inline_06() {
+    assert_eq!(
+        inline("<style>p { color: red }</style><p>a</p><p>b</p>"),
+        "<p style=\"color: red\">a</p><p style=\"color: red\">b</p>"
+    );
+}
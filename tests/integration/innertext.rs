@@ -0,0 +1,100 @@
+use html::InnerText;
+
+fn inner_text(text: &str) -> String {
+    InnerText::new(text).unwrap().as_str().to_string()
+}
+
+#[test]
+fn inner_text_01() {
+    let text = "<html><head><title>Title</title><style>p {}</style></head>\
+                <body>\n  <h1>Header</h1>\n  <p>Some   <b>bold</b>\n text.</p>\
+                <p>Next<br/>line</p>\n  <div>a <span> b </span> c</div>\n</body></html>";
+    assert_eq!(
+        inner_text(text),
+        "Header\n\nSome bold text.\n\nNext\nline\n\na b c"
+    );
+}
+
+#[test]
+fn inner_text_02() {
+    let text = "<div><script>var a = 1;</script><!-- comment --><![CDATA[x]]>\
+                <!--[if mso]><p>mso</p><![endif]-->a&lt;b &amp;&#x20AC;&#8364;&nbsp;&unknown;</div>";
+    assert_eq!(inner_text(text), "a<b &\u{20AC}\u{20AC}\u{A0}&unknown;");
+}
+
+#[test]
+fn inner_text_03() {
+    let text = "<div><pre>\n  a\n    b</pre><textarea> c  d </textarea></div>";
+    assert_eq!(inner_text(text), "  a\n    b\n c  d ");
+}
+
+#[test]
+fn inner_text_04() {
+    let text = "<table><tr><th>a</th><th>b</th></tr>\n<tr><td>1</td><td>2</td></tr></table>";
+    assert_eq!(inner_text(text), "a\tb\n1\t2");
+}
+
+#[test]
+fn inner_text_05() {
+    let source = "<p>Fish &amp;   chips</p>";
+    let text = InnerText::new(source).unwrap();
+    assert_eq!(text.as_str(), "Fish & chips");
+    assert_eq!(text.to_string(), "Fish & chips");
+    assert_eq!(text.source(), source);
+
+    // Copied text is mapped exactly.
+    assert_eq!(text.source_span(0..4).range(), 3..7);
+    assert_eq!(text.source_span(7..12).as_str(), "chips");
+    assert_eq!(text.source_offset(8), 17);
+
+    // Decoded and collapsed characters are mapped to their markup.
+    assert_eq!(text.source_span(5..6).as_str(), "&amp;");
+    assert_eq!(text.source_span(6..7).as_str(), "   ");
+    assert_eq!(text.source_span(0..12).as_str(), "Fish &amp;   chips");
+    assert_eq!(text.source_offset(5), 8);
+
+    // Out of range offsets are clamped.
+    assert_eq!(text.source_span(10..100).as_str(), "ps");
+    assert_eq!(text.source_span(3..3).range(), 6..6);
+}
+
+#[test]
+fn inner_text_06() {
+    assert_eq!(inner_text("<p>   </p>"), "");
+    assert_eq!(
+        InnerText::new("<p>a</p><").unwrap_err().to_string(),
+        "unknown token at 1:9"
+    );
+    let text = InnerText::new("<div><p>a</p><p>b<br>c</p></div>").unwrap();
+    assert_eq!(text.as_str(), "a\n\nb\nc");
+    assert_eq!(text.source_span(1..3).as_str(), "</p>");
+    assert_eq!(text.source_span(4..5).as_str(), "<br>");
+}
+
+#[test]
+fn inner_text_07() {
+    // Stray end tags don't end the hidden content.
+    assert_eq!(
+        inner_text("<p>a<script></i>secret<b></script>b</p>"),
+        "a\nb"
+    );
+    assert_eq!(
+        inner_text("<p>a<script></b>secret<i></script>b</p>"),
+        "a\nb"
+    );
+}
+
+#[test]
+fn inner_text_08() {
+    assert_eq!(inner_text("<p>a</p><p>b</p>"), "a\n\nb");
+    assert_eq!(inner_text("a <b>b</b> c"), "a b c");
+}
+
+#[test]
+fn inner_text_09() {
+    // Numeric references don't need a semicolon, unlisted named references are kept.
+    assert_eq!(
+        inner_text("<p>&#65&#x42;&#0;&#xD800;&copy;&copy&hearts;</p>"),
+        "AB\u{FFFD}\u{FFFD}\u{A9}&copy&hearts;"
+    );
+}
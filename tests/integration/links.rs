@@ -0,0 +1,184 @@
+use html::{LinkKind, Links};
+
+fn links(text: &str) -> Vec<(LinkKind, &str, &str, &str)> {
+    Links::new(text)
+        .unwrap()
+        .iter()
+        .map(|link| {
+            (
+                link.kind(),
+                link.element().as_str(),
+                link.attribute().as_str(),
+                link.url().as_str(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn links_01() {
+    let text = "<html><head><link rel=\"stylesheet\" href=\"main.css\"/>\
+                <LINK REL=\"Shortcut Icon\" href=\" fav.ico \"/><link rel=\"canonical\" href=\"/page\"/>\
+                <link rel=\"preload\" href=\"font.woff2\"/><script src=\"app.js\"></script></head>\
+                <body><a href=\"/about\">About</a><a name=\"top\" href=\"\">Top</a><a>None</a>\
+                <form action=\"/post\"><button formaction=\"/other\">Go</button></form>\
+                <iframe src=\"frame.html\"></iframe><video src=\"movie.mp4\" poster=\"poster.jpg\"></video>\
+                <object data=\"file.swf\"></object><table background=\"bg.gif\"></table></body></html>";
+    assert_eq!(
+        links(text),
+        vec![
+            (LinkKind::Stylesheet, "link", "href", "main.css"),
+            (LinkKind::Image, "LINK", "href", "fav.ico"),
+            (LinkKind::Navigation, "link", "href", "/page"),
+            (LinkKind::Resource, "link", "href", "font.woff2"),
+            (LinkKind::Script, "script", "src", "app.js"),
+            (LinkKind::Navigation, "a", "href", "/about"),
+            (LinkKind::Form, "form", "action", "/post"),
+            (LinkKind::Form, "button", "formaction", "/other"),
+            (LinkKind::Frame, "iframe", "src", "frame.html"),
+            (LinkKind::Media, "video", "src", "movie.mp4"),
+            (LinkKind::Image, "video", "poster", "poster.jpg"),
+            (LinkKind::Resource, "object", "data", "file.swf"),
+            (LinkKind::Image, "table", "background", "bg.gif"),
+        ]
+    );
+}
+
+#[test]
+fn links_02() {
+    let text = "<p><img srcset=\"a.png 1x,b.png 2x , c,d.png 100w\" src=\"e.png\"/>\
+                <picture><source srcset=\"f.webp\"/></picture></p>";
+    assert_eq!(
+        links(text),
+        vec![
+            (LinkKind::Image, "img", "srcset", "a.png"),
+            (LinkKind::Image, "img", "srcset", "b.png"),
+            (LinkKind::Image, "img", "srcset", "c,d.png"),
+            (LinkKind::Image, "img", "src", "e.png"),
+            (LinkKind::Image, "source", "srcset", "f.webp"),
+        ]
+    );
+}
+
+#[test]
+fn links_03() {
+    let text = "<div style=\"background: url(&quot;a.png&quot;), URL( b.png ); color: red\">\
+                <p style=\"background-image: url('c d.png')\">x</p></div>";
+    assert_eq!(
+        links(text),
+        vec![
+            (LinkKind::Image, "div", "style", "a.png"),
+            (LinkKind::Image, "div", "style", "b.png"),
+            (LinkKind::Image, "p", "style", "c d.png"),
+        ]
+    );
+}
+
+#[test]
+fn links_04() {
+    let text = "<head><meta http-equiv=\"Refresh\" content=\"5; URL=&apos;/next&apos;\"/>\
+                <meta http-equiv=\"refresh\" content=\"0;https://example.com/\"/>\
+                <meta name=\"description\" content=\"/not-a-link\"/></head>";
+    assert_eq!(
+        links(text),
+        vec![
+            (LinkKind::Refresh, "meta", "content", "/next"),
+            (LinkKind::Refresh, "meta", "content", "https://example.com/"),
+        ]
+    );
+}
+
+#[test]
+fn links_05() {
+    let text = "<html><head><base href=\"https://example.com/\"/><base href=\"/ignored\"/>\
+                <script>var a = \"<a href='/no'>\";</script></head>\
+                <body><a href=\"/yes\">x</a></body></html>";
+    let links = Links::new(text).unwrap();
+    assert_eq!(links.source(), text);
+    assert_eq!(links.base().unwrap(), "https://example.com/");
+    assert_eq!(links.len(), 3);
+    assert_eq!(links[1].kind(), LinkKind::Base);
+
+    // Spans point into the source, so URLs can be replaced in place.
+    let link = links[2];
+    assert_eq!(link.value(), "/yes");
+    assert_eq!(&text[link.url().range()], "/yes");
+    assert_eq!(link.url().start(), text.find("/yes").unwrap());
+
+    assert!(Links::new("<a href=x>").is_err());
+}
+
+#[test]
+fn links_06() {
+    let text = "<html><head><base href=\"/docs/\"/></head>\
+                <body><a href=\"guide.html?a=1&amp;b=2\">x</a><a href=\"https://other.com/\">y</a></body></html>";
+    let links = Links::new(text).unwrap();
+    assert_eq!(
+        links
+            .base_url(Some("https://example.com/index.html"))
+            .unwrap(),
+        "https://example.com/docs/"
+    );
+    assert_eq!(links.base_url(None).unwrap(), "/docs/");
+
+    let url = Some("https://example.com/index.html");
+    assert_eq!(links.resolve(&links[0], url), "https://example.com/docs/");
+    assert_eq!(
+        links.resolve(&links[1], url),
+        "https://example.com/docs/guide.html?a=1&b=2"
+    );
+    assert_eq!(links.resolve(&links[1], None), "/docs/guide.html?a=1&b=2");
+    assert_eq!(links.resolve(&links[2], url), "https://other.com/");
+
+    let no_base = Links::new("<a href=\"x\">x</a>").unwrap();
+    assert_eq!(no_base.base_url(None), None);
+    assert_eq!(no_base.resolve(&no_base[0], None), "x");
+}
+
+#[test]
+fn links_07() {
+    let text = "<p><a href=\"/a\">x</a><a href='/b'>y</a>\
+                <img srcset=\"a.png 1x, b.png 2x\" style=\"background: url(&quot;c.png&quot;)\"/></p>";
+    let links = Links::new(text).unwrap();
+    let out = links.rewrite(|link| match link.kind() {
+        LinkKind::Navigation => Some(format!("{}?q=\"1\"&r='2'", link.url())),
+        LinkKind::Image if link.url() == "b.png" => None,
+        LinkKind::Image => Some(format!("/my ({})", link.url())),
+        _ => None,
+    });
+    assert_eq!(
+        out,
+        "<p><a href=\"/a?q=&quot;1&quot;&amp;r='2'\">x</a><a href='/b?q=\"1\"&amp;r=&#39;2&#39;'>y</a>\
+         <img srcset=\"/my%20%28a.png%29 1x, b.png 2x\" \
+         style=\"background: url(&quot;/my%20%28c.png%29&quot;)\"/></p>"
+    );
+
+    // The rewritten document is still valid.
+    let links = Links::new(&out).unwrap();
+    assert_eq!(links.len(), 5);
+    assert_eq!(links.rewrite(|_| None), out);
+}
+
+#[test]
+fn links_08() {
+    // Invalid candidates still have a URL.
+    let text = "<img srcset=\"a.png 1é, b.png é\"/>";
+    assert_eq!(
+        links(text),
+        vec![
+            (LinkKind::Image, "img", "srcset", "a.png"),
+            (LinkKind::Image, "img", "srcset", "b.png"),
+        ]
+    );
+}
+
+#[test]
+fn links_09() {
+    assert_eq!(
+        links("<p><a href='/a'>a</a></p><p><a href='/b'>b</a></p>"),
+        vec![
+            (LinkKind::Navigation, "a", "href", "/a"),
+            (LinkKind::Navigation, "a", "href", "/b"),
+        ]
+    );
+}
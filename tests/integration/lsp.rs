@@ -0,0 +1,309 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+use html::LspServer;
+
+const URI: &str = "file:///index.html";
+
+fn frame(messages: &[Value]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for message in messages {
+        let body = message.to_string();
+        write!(data, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    }
+
+    data
+}
+
+fn unframe(mut data: &[u8]) -> Vec<Value> {
+    let mut messages = Vec::new();
+    while !data.is_empty() {
+        let text = std::str::from_utf8(data).unwrap();
+        let header_end = text.find("\r\n\r\n").unwrap();
+        let len: usize = text["Content-Length: ".len()..header_end].parse().unwrap();
+        let body = &data[header_end + 4..header_end + 4 + len];
+        messages.push(serde_json::from_slice(body).unwrap());
+        data = &data[header_end + 4 + len..];
+    }
+
+    messages
+}
+
+fn session(messages: &[Value]) -> Vec<Value> {
+    let mut output = Vec::new();
+    LspServer::new()
+        .run(&frame(messages)[..], &mut output)
+        .unwrap();
+    unframe(&output)
+}
+
+fn open(text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": { "uri": URI, "languageId": "html", "version": 1, "text": text }
+        }
+    })
+}
+
+fn request(id: u32, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 },
+    })
+}
+
+#[test]
+fn lsp_initialize() {
+    let replies = session(&[
+        request(1, "initialize", json!({ "capabilities": {} })),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        request(2, "shutdown", Value::Null),
+        request(3, "textDocument/foldingRange", json!({})),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+        request(4, "shutdown", Value::Null),
+    ]);
+
+    assert_eq!(
+        replies,
+        vec![
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "capabilities": {
+                        "textDocumentSync": 1,
+                        "documentSymbolProvider": true,
+                        "foldingRangeProvider": true,
+                        "definitionProvider": true,
+                    },
+                    "serverInfo": { "name": "htmlparser", "version": env!("CARGO_PKG_VERSION") },
+                }
+            }),
+            json!({ "jsonrpc": "2.0", "id": 2, "result": null }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "error": { "code": -32600, "message": "the server is shut down" }
+            }),
+        ]
+    );
+}
+
+#[test]
+fn lsp_diagnostics() {
+    let replies = session(&[
+        open("<p>\n  😀<a href=x>\n<!x>\n</p>"),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": URI, "version": 2 },
+                "contentChanges": [{ "text": "<p></p>" }]
+            }
+        }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didClose",
+            "params": { "textDocument": { "uri": URI } }
+        }),
+    ]);
+
+    assert_eq!(
+        replies,
+        vec![
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": {
+                    "uri": URI,
+                    "diagnostics": [
+                        {
+                            "range": range((1, 6), (1, 13)),
+                            "severity": 1,
+                            "source": "htmlparser",
+                            "message": "invalid attribute: expected quote mark not 'x'\n\
+                                        help: attribute values must be quoted with '\"' or '''",
+                        },
+                        {
+                            "range": range((2, 0), (2, 1)),
+                            "severity": 1,
+                            "source": "htmlparser",
+                            "message": "unknown token\n\
+                                        help: if this is a text, escape '<' as '&lt;' and '&' as '&amp;'",
+                        },
+                    ],
+                }
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": URI, "diagnostics": [] }
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": URI, "diagnostics": [] }
+            }),
+        ]
+    );
+}
+
+#[test]
+fn lsp_document_symbols() {
+    let replies = session(&[
+        open("<html>\n  <body>\n    <br>\n    <o:p/>\n    <META>\n    <p></p>\n  </body>\n</html>"),
+        request(
+            1,
+            "textDocument/documentSymbol",
+            json!({ "textDocument": { "uri": URI } }),
+        ),
+    ]);
+
+    let symbol = |name: &str, start, end, name_end, children: Value| {
+        json!({
+            "name": name,
+            "kind": 19,
+            "range": range(start, end),
+            "selectionRange": range((start.0, start.1 + 1), name_end),
+            "children": children,
+        })
+    };
+    assert_eq!(
+        replies[1]["result"],
+        json!([symbol(
+            "html",
+            (0, 0),
+            (7, 7),
+            (0, 5),
+            json!([symbol(
+                "body",
+                (1, 2),
+                (6, 9),
+                (1, 7),
+                // Void elements don't contain the following siblings.
+                json!([
+                    symbol("br", (2, 4), (2, 8), (2, 7), json!([])),
+                    symbol("o:p", (3, 4), (3, 10), (3, 8), json!([])),
+                    symbol("META", (4, 4), (4, 10), (4, 9), json!([])),
+                    symbol("p", (5, 4), (5, 11), (5, 6), json!([])),
+                ])
+            )])
+        )])
+    );
+}
+
+#[test]
+fn lsp_folding_ranges() {
+    let replies = session(&[
+        open(
+            "<div>\n<!--\n  comment\n-->\n<!--[if mso]>\n<p>\n</p>\n<![endif]-->\n<span></span>\n</div>",
+        ),
+        request(
+            1,
+            "textDocument/foldingRange",
+            json!({ "textDocument": { "uri": URI } }),
+        ),
+    ]);
+
+    assert_eq!(
+        replies[1]["result"],
+        json!([
+            { "startLine": 0, "endLine": 8 },
+            { "startLine": 1, "endLine": 3, "kind": "comment" },
+            { "startLine": 4, "endLine": 6, "kind": "region" },
+        ])
+    );
+}
+
+#[test]
+fn lsp_matching_tag() {
+    let text = "<div>\n  <p>😀</p>\n</div>";
+    let definition = |id, line, character| {
+        request(
+            id,
+            "textDocument/definition",
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character }
+            }),
+        )
+    };
+
+    let replies = session(&[
+        open(text),
+        definition(1, 0, 2),
+        definition(2, 1, 9),
+        definition(3, 1, 1),
+    ]);
+
+    assert_eq!(
+        replies[1]["result"],
+        json!({ "uri": URI, "range": range((2, 2), (2, 5)) })
+    );
+    assert_eq!(
+        replies[2]["result"],
+        json!({ "uri": URI, "range": range((1, 3), (1, 4)) })
+    );
+    assert_eq!(replies[3]["result"], Value::Null);
+}
+
+#[test]
+fn lsp_errors() {
+    let mut input = b"Content-Length: 5\r\n\r\n{oops".to_vec();
+    input.extend(frame(&[
+        request(1, "unknown", json!({})),
+        request(
+            2,
+            "textDocument/documentSymbol",
+            json!({ "textDocument": { "uri": "file:///unknown.html" } }),
+        ),
+    ]));
+
+    let mut output = Vec::new();
+    LspServer::new().run(&input[..], &mut output).unwrap();
+    let replies = unframe(&output);
+
+    assert_eq!(replies[0]["id"], Value::Null);
+    assert_eq!(replies[0]["error"]["code"], -32700);
+    assert_eq!(
+        replies[1]["error"],
+        json!({ "code": -32601, "message": "method not found" })
+    );
+    assert_eq!(
+        replies[2]["error"],
+        json!({ "code": -32602, "message": "unknown document" })
+    );
+}
+
+#[test]
+fn lsp_stdio() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_htmlparser-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&frame(&[
+            request(1, "shutdown", Value::Null),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]))
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        unframe(&output.stdout),
+        vec![json!({ "jsonrpc": "2.0", "id": 1, "result": null })]
+    );
+}
@@ -5,10 +5,39 @@ mod token;
 
 mod api;
 mod cdata;
+#[cfg(feature = "cli")]
+mod cli;
 mod comments;
 mod condition;
+mod diagnostic;
 mod doctype;
 mod document;
 mod elements;
+#[cfg(feature = "std")]
+mod format;
+mod handler;
+#[cfg(feature = "std")]
+mod inline;
+#[cfg(feature = "std")]
+mod innertext;
+#[cfg(feature = "std")]
+mod links;
+#[cfg(feature = "lsp")]
+mod lsp;
+#[cfg(feature = "std")]
+mod markdown;
+#[cfg(feature = "std")]
+mod minify;
+mod mso;
+#[cfg(feature = "std")]
+mod owned;
 mod pi;
+#[cfg(feature = "std")]
+mod sanitize;
+#[cfg(all(feature = "std", feature = "serde"))]
+mod serialization;
+mod srcset;
+mod style;
 mod text;
+#[cfg(feature = "std")]
+mod url;
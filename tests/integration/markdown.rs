@@ -0,0 +1,88 @@
+use html::MarkdownConverter;
+
+fn convert(text: &str) -> String {
+    MarkdownConverter::new().convert(text).unwrap()
+}
+
+#[test]
+fn markdown_01() {
+    let text = "<html><head><title>Title</title></head><body>\
+                <h1>Title</h1><p>Some <b>bold</b>,  <em> it </em>and <s>old</s>.</p>\
+                <hr/><h2>  Next <i>part</i> </h2></body></html>";
+    assert_eq!(
+        convert(text),
+        "# Title\n\nSome **bold**, *it* and ~~old~~.\n\n---\n\n## Next *part*\n"
+    );
+}
+
+#[test]
+fn markdown_02() {
+    let text = "<p>A <a href=\"/x\" title=\"The x\">link</a>, \
+                <img src=\"a b.png\" alt=\"pic\"/> and <code>a`b</code>.</p>";
+    assert_eq!(
+        convert(text),
+        "A [link](/x \"The x\"), ![pic](<a b.png>) and ``a`b``.\n"
+    );
+}
+
+#[test]
+fn markdown_03() {
+    let text = "<ul><li>One<ul><li>Nested</li></ul></li><li>Two</li></ul>";
+    assert_eq!(convert(text), "- One\n  - Nested\n- Two\n");
+
+    let text = "<ol start=\"3\"><li><p>A</p><p>B</p></li><li>C</li></ol>";
+    assert_eq!(convert(text), "3. A\n\n   B\n4. C\n");
+
+    let text = "<ul><li>One</li></ul>";
+    assert_eq!(
+        MarkdownConverter::new()
+            .with_bullet('*')
+            .convert(text)
+            .unwrap(),
+        "* One\n"
+    );
+}
+
+#[test]
+fn markdown_04() {
+    let text = "<pre><code class=\"language-rust\">fn main() {\n    1 &lt; 2;\n}\n</code></pre>";
+    assert_eq!(convert(text), "```rust\nfn main() {\n    1 < 2;\n}\n```\n");
+
+    let text = "<pre>a\n```\nb</pre>";
+    assert_eq!(convert(text), "````\na\n```\nb\n````\n");
+}
+
+#[test]
+fn markdown_05() {
+    let text = "<table><thead><tr><th>a</th><th align=\"right\">b|c</th></tr></thead>\
+                <tbody><tr><td>1</td><td>2<br/>3</td></tr><tr><td>4</td></tr></tbody></table>";
+    assert_eq!(
+        convert(text),
+        "| a | b\\|c |\n| --- | ---: |\n| 1 | 2<br>3 |\n| 4 |  |\n"
+    );
+}
+
+#[test]
+fn markdown_06() {
+    let text = "<blockquote><p>Quote</p><blockquote><p>Nested</p></blockquote></blockquote>";
+    assert_eq!(convert(text), "> Quote\n>\n> > Nested\n");
+}
+
+#[test]
+fn markdown_07() {
+    // Unknown elements are copied as HTML.
+    let text = "<div><p>x<sup>2</sup></p><dl><dt>a</dt></dl></div>";
+    assert_eq!(convert(text), "x<sup>2</sup>\n\n<dl><dt>a</dt></dl>\n");
+
+    // Markdown characters are escaped.
+    let text = "<p>1. *a* [b] &amp;amp; &lt;c&gt;<br/># d</p>";
+    assert_eq!(
+        convert(text),
+        "1\\. \\*a\\* \\[b\\] \\&amp; \\<c>  \n\\# d\n"
+    );
+}
+
+#[test]
+fn markdown_08() {
+    assert_eq!(convert("<h1>a</h1><p>b</p>"), "# a\n\nb\n");
+}
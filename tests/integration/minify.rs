@@ -0,0 +1,128 @@
+use html::Minifier;
+
+fn minify(text: &str) -> String {
+    Minifier::new().minify(text).unwrap()
+}
+
+fn aggressive(text: &str) -> String {
+    Minifier::aggressive().minify(text).unwrap()
+}
+
+#[test]
+fn minify_01() {
+    assert_eq!(
+        minify("<div>\n  <p>\n    Some   <b>bold</b> <i>text</i>\n  </p>\n\n  <p>x</p>\n</div>"),
+        "<div><p>Some <b>bold</b> <i>text</i></p><p>x</p></div>"
+    );
+}
+
+#[test]
+fn minify_02() {
+    // Preformatted elements are kept as is.
+    let text = "<div>\n<pre>  a\n   b </pre>\n<textarea> c  </textarea>\
+                <script>  var x = 1;\n</script></div>";
+    assert_eq!(
+        minify(text),
+        "<div><pre>  a\n   b </pre><textarea> c  </textarea>\
+         <script>  var x = 1;\n</script></div>"
+    );
+}
+
+#[test]
+fn minify_03() {
+    let text = "<div><!-- comment --><!--[if mso]><table><![endif]-->a <!-- b --> c</div>";
+    assert_eq!(
+        minify(text),
+        "<div><!--[if mso]><table><![endif]-->a c</div>"
+    );
+    assert_eq!(
+        Minifier::new()
+            .with_remove_comments(false)
+            .minify(text)
+            .unwrap(),
+        "<div><!-- comment --><!--[if mso]><table><![endif]-->a <!-- b --> c</div>"
+    );
+}
+
+#[test]
+fn minify_04() {
+    let text = "<form><INPUT Type=\"checkbox\" checked='checked' disabled value=\"\" \
+                data-x=\"a b\" data-y='\"'/></form>";
+    assert_eq!(
+        minify(text),
+        "<form><input Type=\"checkbox\" checked disabled value data-x=\"a b\" data-y='\"'></form>"
+    );
+    assert_eq!(
+        aggressive(text),
+        "<form><input Type=checkbox checked disabled value data-x=\"a b\" data-y='\"'></form>"
+    );
+    assert_eq!(
+        Minifier::new()
+            .with_collapse_boolean_attributes(false)
+            .with_lowercase_tag_names(false)
+            .minify(text)
+            .unwrap(),
+        "<form><INPUT Type=\"checkbox\" checked='checked' disabled value=\"\" \
+         data-x=\"a b\" data-y='\"'></form>"
+    );
+}
+
+#[test]
+fn minify_05() {
+    let text = "<table>\n<thead><tr><th>a</th></tr></thead>\n\
+                <tbody><tr><td>1</td><td>2</td></tr>\n<tr><td>3</td></tr></tbody></table>";
+    assert_eq!(
+        aggressive(text),
+        "<table><thead><tr><th>a<tbody><tr><td>1<td>2<tr><td>3</table>"
+    );
+}
+
+#[test]
+fn minify_06() {
+    // A paragraph end tag is only omitted before a block or the parent end.
+    assert_eq!(
+        aggressive("<div><p>a</p><p>b</p> <span>c</span><p>d</p></div>"),
+        "<div><p>a<p>b</p><span>c</span><p>d</div>"
+    );
+    assert_eq!(aggressive("<a><p>a</p></a>"), "<a><p>a</p></a>");
+    assert_eq!(
+        aggressive("<dl><dt>a</dt><dd>b</dd>\n<dt>c</dt><dd>d</dd></dl>"),
+        "<dl><dt>a<dd>b<dt>c<dd>d</dl>"
+    );
+}
+
+#[test]
+fn minify_07() {
+    // Self-closing tags are kept only in foreign content.
+    assert_eq!(
+        aggressive("<div><span/><br/><svg viewBox='0 0 1 1'><path d='M0'/></svg></div>"),
+        "<div><span></span><br><svg viewBox='0 0 1 1'><path d='M0'/></svg></div>"
+    );
+}
+
+#[test]
+fn minify_08() {
+    assert_eq!(
+        Minifier::new()
+            .with_collapse_whitespace(false)
+            .minify("<p>\n  a  </p>")
+            .unwrap(),
+        "<p>\n  a  </p>"
+    );
+    assert_eq!(
+        minify("<!DOCTYPE html>\n<html>\n<head><title>a</title></head>\n<body>b</body>\n</html>"),
+        "<!DOCTYPE html><html><head><title>a</title></head><body>b</body></html>"
+    );
+    assert_eq!(
+        aggressive(
+            "<!DOCTYPE html>\n<html>\n<head><title>a</title></head>\n<body>b</body>\n</html>"
+        ),
+        "<!DOCTYPE html><html><head><title>a</title><body>b"
+    );
+}
+
+#[test]
+fn minify_09() {
+    assert_eq!(minify("<p>a</p>\n\n<p>b</p>\n"), "<p>a</p><p>b</p>");
+    assert_eq!(minify("a  <b>b</b>  c"), "a <b>b</b> c");
+}
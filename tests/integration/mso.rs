@@ -0,0 +1,152 @@
+use crate::token::*;
+
+fn filter(text: &str, vml: html::VmlMode) -> Vec<Token<'_>> {
+    html::MsoFilter::new(html::Tokenizer::from(text), vml)
+        .map(to_test_token)
+        .collect()
+}
+
+#[test]
+fn namespace_01() {
+    assert_eq!(
+        html::MsoNamespace::from_uri("urn:schemas-microsoft-com:office:office"),
+        Some(html::MsoNamespace::Office)
+    );
+    assert_eq!(
+        html::MsoNamespace::from_uri("http://www.w3.org/2000/svg"),
+        None
+    );
+    assert_eq!(
+        html::MsoNamespace::from_prefix("v"),
+        Some(html::MsoNamespace::Vml)
+    );
+    assert_eq!(html::MsoNamespace::Word.prefix(), "w");
+}
+
+#[test]
+fn outlook_profile_01() {
+    let condition = html::Condition::parse("if gte mso 9").unwrap();
+    assert!(condition.evaluate(&html::Profile::OUTLOOK_2007));
+    let condition = html::Condition::parse("if gte vml 1").unwrap();
+    assert!(condition.evaluate(&html::Profile::OUTLOOK_2016));
+    let condition = html::Condition::parse("if mso 16").unwrap();
+    assert!(!condition.evaluate(&html::Profile::OUTLOOK_2013));
+}
+
+#[test]
+fn office_paragraph_01() {
+    assert_eq!(
+        filter("<p>text<o:p></o:p></p>", html::VmlMode::Keep),
+        vec![
+            Token::ElementStart("", "p", 0..2),
+            Token::ElementEnd(ElementEnd::Open, 2..3),
+            Token::Text("text", 3..7),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 18..22),
+        ]
+    );
+}
+
+#[test]
+fn office_paragraph_02() {
+    assert_eq!(
+        filter(
+            "<p><o:p class='x'>&nbsp;</o:p><o:p/></p>",
+            html::VmlMode::Keep
+        ),
+        vec![
+            Token::ElementStart("", "p", 0..2),
+            Token::ElementEnd(ElementEnd::Open, 2..3),
+            Token::Text("&nbsp;", 18..24),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 36..40),
+        ]
+    );
+}
+
+static BUTTON: &str = "<td><!--[if mso]>\
+<v:roundrect href='#'><v:fill/><a>Go</a></v:roundrect>\
+<![endif]--><!--[if !mso]><!--><a>Go</a><!--<![endif]--></td>";
+
+#[test]
+fn vml_keep_01() {
+    let tokens = filter(BUTTON, html::VmlMode::Keep);
+    assert_eq!(tokens.len(), 21);
+    assert_eq!(tokens[3], Token::ElementStart("v", "roundrect", 17..29));
+}
+
+#[test]
+fn vml_strip_01() {
+    assert_eq!(
+        filter(BUTTON, html::VmlMode::Strip),
+        vec![
+            Token::ElementStart("", "td", 0..3),
+            Token::ElementEnd(ElementEnd::Open, 3..4),
+            Token::ConditionalCommentStart("if !mso", 83..102),
+            Token::ElementStart("", "a", 102..104),
+            Token::ElementEnd(ElementEnd::Open, 104..105),
+            Token::Text("Go", 105..107),
+            Token::ElementEnd(ElementEnd::Close("", "a"), 107..111),
+            Token::ConditionalCommentEnd(83..102, 111..127),
+            Token::ElementEnd(ElementEnd::Close("", "td"), 127..132),
+        ]
+    );
+}
+
+#[test]
+fn vml_strip_02() {
+    assert_eq!(
+        filter(
+            "<div xmlns:w='urn:schemas-microsoft-com:vml'><w:oval><w:oval/></w:oval>!</div>",
+            html::VmlMode::Strip
+        ),
+        vec![
+            Token::ElementStart("", "div", 0..4),
+            Token::Attribute("xmlns", "w", Some("urn:schemas-microsoft-com:vml"), 5..44),
+            Token::ElementEnd(ElementEnd::Open, 44..45),
+            Token::Text("!", 71..72),
+            Token::ElementEnd(ElementEnd::Close("", "div"), 72..78),
+        ]
+    );
+}
+
+#[test]
+fn vml_strip_03() {
+    // Nested comments without VML are kept, the following ones are still checked.
+    assert_eq!(
+        filter(
+            "<p><!--[if mso]><![if gte mso 9]><b/><![endif]><![endif]-->\
+             <!--[if vml]><v:oval/><![endif]--></p>",
+            html::VmlMode::Strip
+        ),
+        vec![
+            Token::ElementStart("", "p", 0..2),
+            Token::ElementEnd(ElementEnd::Open, 2..3),
+            Token::ConditionalCommentStart("if mso", 3..16),
+            Token::ConditionalCommentStart("if gte mso 9", 16..33),
+            Token::ElementStart("", "b", 33..35),
+            Token::ElementEnd(ElementEnd::Empty, 35..37),
+            Token::ConditionalCommentEnd(16..33, 37..47),
+            Token::ConditionalCommentEnd(3..16, 47..59),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 93..97),
+        ]
+    );
+}
+
+#[test]
+fn vml_strip_04() {
+    // Void elements inside VML don't hide the following content.
+    let text = "<v:rect><v:textbox>a<br>b</v:textbox></v:rect><p>after</p>";
+    assert_eq!(
+        html::MsoFilter::new(
+            html::Tokenizer::from_fragment(text, 0..text.len()),
+            html::VmlMode::Strip
+        )
+        .map(to_test_token)
+        .collect::<Vec<_>>(),
+        vec![
+            Token::ElementStart("", "p", 46..48),
+            Token::ElementEnd(ElementEnd::Open, 48..49),
+            Token::Text("after", 49..54),
+            Token::ElementEnd(ElementEnd::Close("", "p"), 54..58),
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+use std::sync::mpsc;
+use std::thread;
+
+use crate::token::*;
+
+#[test]
+fn owned_01() {
+    let text = String::from("<a x='1'>text<!--c--></a>");
+    let tokens: Vec<html::OwnedToken> = html::Tokenizer::from(text.as_str())
+        .map(|t| t.unwrap().to_owned())
+        .collect();
+    drop(text);
+
+    let result: Vec<Token> = tokens
+        .iter()
+        .map(|t| to_test_token(Ok(t.as_token())))
+        .collect();
+    assert_eq!(
+        result,
+        vec![
+            Token::ElementStart("", "a", 0..2),
+            Token::Attribute("", "x", Some("1"), 3..8),
+            Token::ElementEnd(ElementEnd::Open, 8..9),
+            Token::Text("text", 9..13),
+            Token::Comment("c", 13..21),
+            Token::ElementEnd(ElementEnd::Close("", "a"), 21..25),
+        ]
+    );
+}
+
+#[test]
+fn owned_02() {
+    let text = "<!DOCTYPE x PUBLIC 'a' 'b' [<!ENTITY e SYSTEM 'c'>]><![if mso]><![endif]>";
+    for token in html::Tokenizer::from(text) {
+        let token = token.unwrap();
+        let owned = token.to_owned();
+        assert_eq!(owned.as_token(), token);
+        assert_eq!(owned.span().range(), token.span().range());
+        assert_eq!(*owned.span(), token.span().as_str());
+    }
+}
+
+#[test]
+fn owned_send() {
+    let (tx, rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        let text = String::from("<p>text</p>");
+        for token in html::Tokenizer::from(text.as_str()) {
+            tx.send(html::OwnedToken::from(token.unwrap())).unwrap();
+        }
+    });
+    handle.join().unwrap();
+
+    let spans: Vec<_> = rx.iter().map(|t| t.span().range()).collect();
+    assert_eq!(spans, vec![0..2, 2..3, 3..7, 7..11]);
+}
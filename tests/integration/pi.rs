@@ -21,7 +21,9 @@ test!(
 test!(
     pi_err_01,
     "<??xml \t\n m?>",
-    Token::Error("invalid processing instruction at 1:1 cause invalid name token".to_string())
+    Token::Error(
+        "invalid processing instruction at 1:1 cause invalid name token at 1:3".to_string()
+    )
 );
 
 test!(
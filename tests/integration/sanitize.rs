@@ -0,0 +1,123 @@
+use html::Sanitizer;
+
+fn sanitize(text: &str) -> String {
+    Sanitizer::new().sanitize(text).unwrap()
+}
+
+#[test]
+fn sanitize_01() {
+    assert_eq!(
+        sanitize("<div><p class='a' title=\"b\">Hi &amp; bye & <b>x</b></p><br/><hr></hr></div>"),
+        "<div><p title=\"b\">Hi &amp; bye &amp; <b>x</b></p><br/><hr></div>"
+    );
+}
+
+#[test]
+fn sanitize_02() {
+    // Disallowed elements keep their content, unlike scripts and styles.
+    assert_eq!(
+        sanitize(
+            "<p><font color='red'>a</font><script>b</script><style>c</style>\
+                  <script src='x.js'/>d</p>"
+        ),
+        "<p>ad</p>"
+    );
+}
+
+#[test]
+fn sanitize_03() {
+    assert_eq!(
+        sanitize("<a href='javascript:alert(1)' onclick='alert(1)' onmouseover='x'>a</a>"),
+        "<a>a</a>"
+    );
+    assert_eq!(
+        sanitize("<a href=' JaVa&#x09;script&colon;alert(1)'>a</a>"),
+        "<a>a</a>"
+    );
+    assert_eq!(sanitize("<a href='&#106avascript:x'>a</a>"), "<a>a</a>");
+    assert_eq!(sanitize("<img src='data:image/png;base64,AA'/>"), "<img/>");
+    assert_eq!(
+        sanitize("<a href='https://a.com/?q=\"x\"&amp;b'>a</a>"),
+        "<a href=\"https://a.com/?q=&quot;x&quot;&amp;b\">a</a>"
+    );
+    assert_eq!(
+        sanitize("<p><a href='/path:x'>a</a><a href='mailto:a@b.c'>b</a></p>"),
+        "<p><a href=\"/path:x\">a</a><a href=\"mailto:a@b.c\">b</a></p>"
+    );
+}
+
+#[test]
+fn sanitize_04() {
+    assert_eq!(
+        sanitize("<div><!-- comment --><![CDATA[<b>]]><!--[if IE]><p>ie</p><![endif]-->a</div>"),
+        "<div>a</div>"
+    );
+}
+
+#[test]
+fn sanitize_05() {
+    // Unclosed and unbalanced elements are fixed.
+    assert_eq!(
+        sanitize("<div><b><i>a</b></span>"),
+        "<div><b><i>a</i></b></div>"
+    );
+}
+
+#[test]
+fn sanitize_06() {
+    let sanitizer = Sanitizer::empty()
+        .allow_tags(&["A", "svg:a"])
+        .allow_attributes("a", &["href", "onclick"])
+        .allow_url_schemes(&["ftp"]);
+    assert_eq!(
+        sanitizer
+            .sanitize("<p><a href='ftp://a' onclick='x'>a</a><a href='http://b'>b</a></p>")
+            .unwrap(),
+        "<a href=\"ftp://a\">a</a><a>b</a>"
+    );
+    assert_eq!(
+        Sanitizer::empty().sanitize("<p>1 &lt; 2</p>").unwrap(),
+        "1 &lt; 2"
+    );
+}
+
+#[test]
+fn sanitize_07() {
+    assert_eq!(
+        Sanitizer::new()
+            .sanitize("<p>a</p><")
+            .unwrap_err()
+            .to_string(),
+        "unknown token at 1:9"
+    );
+}
+
+#[test]
+fn sanitize_08() {
+    // Stray end tags don't end the removed content.
+    assert_eq!(
+        sanitize("<p>a<script></b>alert(1)<i></script>b</p>"),
+        "<p>ab</p>"
+    );
+    assert_eq!(sanitize("<p>a<style></i>x<b></style>b</p>"), "<p>ab</p>");
+}
+
+#[test]
+fn sanitize_09() {
+    // Leading spaces and control characters are skipped by browsers.
+    assert_eq!(sanitize("<a href='&#32;javascript:x'>a</a>"), "<a>a</a>");
+    assert_eq!(sanitize("<a href='&#x20;javascript:x'>a</a>"), "<a>a</a>");
+    assert_eq!(sanitize("<a href='&#1;javascript:x'>a</a>"), "<a>a</a>");
+    assert_eq!(sanitize("<a href='&#9;javascript:x'>a</a>"), "<a>a</a>");
+    assert_eq!(sanitize("<a href='&#0;javascript:x'>a</a>"), "<a>a</a>");
+    assert_eq!(
+        sanitize("<a href='&#32;/path'>a</a>"),
+        "<a href=\"&#32;/path\">a</a>"
+    );
+}
+
+#[test]
+fn sanitize_10() {
+    assert_eq!(sanitize("<p>a</p><p>b</p>"), "<p>a</p><p>b</p>");
+    assert_eq!(sanitize("a <b>b</b> c"), "a <b>b</b> c");
+}
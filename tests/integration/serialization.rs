@@ -0,0 +1,76 @@
+#[test]
+fn serialize_token_01() {
+    let token = html::Tokenizer::from("<svg:rect x='1'/>")
+        .nth(1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        serde_json::to_string(&token).unwrap(),
+        r#"{"Attribute":{"prefix":{"text":"","range":{"start":0,"end":0}},"local":{"text":"x","range":{"start":10,"end":11}},"value":{"text":"1","range":{"start":13,"end":14}},"span":{"text":"x='1'","range":{"start":10,"end":15}}}}"#
+    );
+}
+
+#[test]
+fn serialize_token_02() {
+    let token = html::Tokenizer::from("<a></a>").nth(2).unwrap().unwrap();
+    assert_eq!(
+        serde_json::to_string(&token).unwrap(),
+        r#"{"ElementEnd":{"end":{"Close":[{"text":"","range":{"start":0,"end":0}},{"text":"a","range":{"start":5,"end":6}}]},"span":{"text":"</a>","range":{"start":3,"end":7}}}}"#
+    );
+}
+
+#[test]
+fn serialize_error_01() {
+    let err = html::Tokenizer::from("<a x='1' /")
+        .nth(2)
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        serde_json::to_string(&err).unwrap(),
+        r#"{"InvalidAttribute":[{"UnexpectedEndOfStream":{"row":1,"col":11,"offset":10}},{"row":1,"col":9,"offset":8}]}"#
+    );
+}
+
+#[test]
+fn serialize_error_02() {
+    let err = html::Tokenizer::from("<!DOCTYPE a ]")
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        serde_json::to_string(&err).unwrap(),
+        r#"{"InvalidDoctype":[{"InvalidCharMultiple":[93,[91,62],{"row":1,"col":13,"offset":12}]},{"row":1,"col":1,"offset":0}]}"#
+    );
+}
+
+#[test]
+fn text_pos_roundtrip() {
+    let pos = html::TextPos::new(3, 14, 40);
+    let json = serde_json::to_string(&pos).unwrap();
+    assert_eq!(json, r#"{"row":3,"col":14,"offset":40}"#);
+    assert_eq!(serde_json::from_str::<html::TextPos>(&json).unwrap(), pos);
+
+    // The offset is optional.
+    let pos = serde_json::from_str::<html::TextPos>(r#"{"row":3,"col":14}"#).unwrap();
+    assert_eq!(pos, html::TextPos::new(3, 14, 0));
+}
+
+#[test]
+fn owned_token_roundtrip() {
+    let text =
+        "<!DOCTYPE x SYSTEM 'a' [<!ENTITY e 'v'>]><![if mso]><a b='c'>t<![CDATA[d]]></a><![endif]>";
+    for token in html::Tokenizer::from(text) {
+        let token = token.unwrap();
+        let json = serde_json::to_string(&token).unwrap();
+        let owned: html::OwnedToken = serde_json::from_str(&json).unwrap();
+        assert_eq!(owned.as_token(), token);
+        assert_eq!(serde_json::to_string(&owned).unwrap(), json);
+    }
+}
+
+#[test]
+fn owned_span_err_01() {
+    let json = r#"{"text":"abc","range":{"start":1,"end":3}}"#;
+    let err = serde_json::from_str::<html::OwnedStrSpan>(json).unwrap_err();
+    assert_eq!(err.to_string(), "span range doesn't match the text length");
+}
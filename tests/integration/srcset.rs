@@ -0,0 +1,157 @@
+use html::{Sizes, Srcset, StrSpan, Token, Tokenizer};
+
+/// The URL, descriptors, width, density, height and validity.
+type Candidate<'a> = (
+    &'a str,
+    &'a str,
+    Option<u32>,
+    Option<f64>,
+    Option<u32>,
+    bool,
+);
+
+fn candidates(text: &str) -> Vec<Candidate<'_>> {
+    Srcset::from(text)
+        .map(|candidate| {
+            (
+                candidate.url().as_str(),
+                candidate.descriptors().as_str(),
+                candidate.width(),
+                candidate.density(),
+                candidate.height(),
+                candidate.is_valid(),
+            )
+        })
+        .collect()
+}
+
+fn sizes(text: &str) -> Vec<(Option<&str>, &str, bool)> {
+    Sizes::from(text)
+        .map(|size| {
+            (
+                size.condition().map(|condition| condition.as_str()),
+                size.size().as_str(),
+                size.is_valid(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn srcset_01() {
+    assert_eq!(
+        candidates(" a.jpg, b.jpg 2x,c.jpg 1.5x ,d.jpg 100w 50h"),
+        vec![
+            ("a.jpg", "", None, None, None, true),
+            ("b.jpg", "2x", None, Some(2.0), None, true),
+            ("c.jpg", "1.5x", None, Some(1.5), None, true),
+            ("d.jpg", "100w 50h", Some(100), None, Some(50), true),
+        ]
+    );
+    assert_eq!(candidates(""), vec![]);
+    assert_eq!(candidates(" , ,"), vec![]);
+}
+
+#[test]
+fn srcset_02() {
+    // URLs end at whitespace only, except for the trailing commas.
+    assert_eq!(
+        candidates("a,b.jpg 1x, c.jpg,, d.jpg,e.jpg 2x, data:image/png;base64,iVBOR 3x"),
+        vec![
+            ("a,b.jpg", "1x", None, Some(1.0), None, true),
+            ("c.jpg", "", None, None, None, true),
+            ("d.jpg,e.jpg", "2x", None, Some(2.0), None, true),
+            (
+                "data:image/png;base64,iVBOR",
+                "3x",
+                None,
+                Some(3.0),
+                None,
+                true
+            ),
+        ]
+    );
+}
+
+#[test]
+fn srcset_03() {
+    let invalid: Vec<_> = candidates(
+        "a.jpg 1x 2x, b.jpg 50h, c.jpg 0w, d.jpg f(1, 2) 3x, e.jpg -1x, f.jpg 1.x, g.jpg 10W",
+    )
+    .into_iter()
+    .map(|(url, descriptors, .., valid)| (url, descriptors, valid))
+    .collect();
+    assert_eq!(
+        invalid,
+        vec![
+            ("a.jpg", "1x 2x", false),
+            ("b.jpg", "50h", false),
+            ("c.jpg", "0w", false),
+            ("d.jpg", "f(1, 2) 3x", false),
+            ("e.jpg", "-1x", false),
+            ("f.jpg", "1.x", false),
+            ("g.jpg", "10W", false),
+        ]
+    );
+}
+
+#[test]
+fn srcset_04() {
+    let text = "<img srcset=\"a.jpg 1x, b.jpg 2x\"/>";
+    let value = Tokenizer::from(text)
+        .find_map(|token| match token {
+            Ok(Token::Attribute { value, .. }) => value,
+            _ => None,
+        })
+        .unwrap();
+    let candidate = Srcset::new(value).nth(1).unwrap();
+    assert_eq!(candidate.url().range(), 23..28);
+    assert_eq!(candidate.descriptors().range(), 29..31);
+}
+
+#[test]
+fn srcset_05() {
+    // Descriptors ending with a non-ASCII character are invalid.
+    assert_eq!(
+        candidates("a.png é, b.png 1é, c.png 2x"),
+        vec![
+            ("a.png", "é", None, None, None, false),
+            ("b.png", "1é", None, None, None, false),
+            ("c.png", "2x", None, Some(2.0), None, true),
+        ]
+    );
+}
+
+#[test]
+fn sizes_01() {
+    assert_eq!(
+        sizes("(max-width: 600px) 480px, (min-width:40em)calc(100vw - 2em), 100vw"),
+        vec![
+            (Some("(max-width: 600px)"), "480px", true),
+            (Some("(min-width:40em)"), "calc(100vw - 2em)", true),
+            (None, "100vw", true),
+        ]
+    );
+    assert_eq!(sizes("auto"), vec![(None, "auto", true)]);
+    assert_eq!(sizes(""), vec![]);
+}
+
+#[test]
+fn sizes_02() {
+    assert_eq!(
+        sizes("50%, (a) -1px, 1, 0, MAX(1px, 2px), , (b) 1.5E1REM, (c)"),
+        vec![
+            (None, "50%", false),
+            (Some("(a)"), "-1px", false),
+            (None, "1", false),
+            (None, "0", true),
+            (None, "MAX(1px, 2px)", true),
+            (Some("(b)"), "1.5E1REM", true),
+            (Some("(c)"), "", false),
+        ]
+    );
+
+    let value = StrSpan::from("(a) 1px, 2px");
+    let size = Sizes::new(value).nth(1).unwrap();
+    assert_eq!(size.size().range(), 9..12);
+}
@@ -0,0 +1,85 @@
+use html::{StyleDeclarations, Token, Tokenizer};
+
+fn declarations(text: &str) -> Vec<(&str, &str, bool)> {
+    StyleDeclarations::from(text)
+        .map(|declaration| {
+            (
+                declaration.property().as_str(),
+                declaration.value().as_str(),
+                declaration.is_important(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn style_01() {
+    assert_eq!(
+        declarations(" color : red ;; FONT-SIZE:12px!IMPORTANT; margin: 0 ! important ;"),
+        vec![
+            ("color", "red", false),
+            ("FONT-SIZE", "12px", true),
+            ("margin", "0", true),
+        ]
+    );
+    assert_eq!(declarations(""), vec![]);
+    assert_eq!(declarations(" ; ; "), vec![]);
+}
+
+#[test]
+fn style_02() {
+    // Semicolons and colons inside strings, comments and blocks.
+    assert_eq!(
+        declarations(
+            "content: \"a;b\"; font-family: 'x\\';y', &quot;A;B&quot;; \
+             /* c: d; */ background: url(data:image/png;base64,AA==); --x: { a; b }"
+        ),
+        vec![
+            ("content", "\"a;b\"", false),
+            ("font-family", "'x\\';y', &quot;A;B&quot;", false),
+            ("background", "url(data:image/png;base64,AA==)", false),
+            ("--x", "{ a; b }", false),
+        ]
+    );
+}
+
+#[test]
+fn style_03() {
+    // Invalid declarations are skipped.
+    assert_eq!(
+        declarations("color; : red; width: 1px; important; a: !important; b: x important"),
+        vec![
+            ("width", "1px", false),
+            ("a", "", true),
+            ("b", "x important", false),
+        ]
+    );
+    assert_eq!(
+        declarations("content: \"unclosed; color: red"),
+        vec![("content", "\"unclosed; color: red", false)]
+    );
+}
+
+#[test]
+fn style_04() {
+    let text = "<p style=\"color: red; font-size: 12px !important\">x</p>";
+    let value = Tokenizer::from(text)
+        .find_map(|token| match token {
+            Ok(Token::Attribute { value, .. }) => value,
+            _ => None,
+        })
+        .unwrap();
+
+    let declaration = StyleDeclarations::new(value).nth(1).unwrap();
+    assert_eq!(declaration.property().range(), 22..31);
+    assert_eq!(declaration.value().range(), 33..37);
+    assert_eq!(declaration.span().as_str(), "font-size: 12px !important");
+
+    // Edit a single property in place.
+    let range = declaration.value().range();
+    let edited = format!("{}14px{}", &text[..range.start], &text[range.end..]);
+    assert_eq!(
+        edited,
+        "<p style=\"color: red; font-size: 14px !important\">x</p>"
+    );
+}
@@ -70,7 +70,7 @@ test!(
     Token::ElementStart("", "p", 0..2),
     Token::ElementEnd(ElementEnd::Open, 2..3),
     Token::Error(
-        "invalid character data at 1:4 cause ']]>' is not allowed inside a character data"
+        "invalid character data at 1:4 cause ']]>' is not allowed inside a character data at 1:4"
             .to_string()
     )
 );
@@ -5,7 +5,7 @@ pub enum Token<'a> {
     Declaration(&'a str, Option<&'a str>, Option<bool>, Range),
     PI(&'a str, Option<&'a str>, Range),
     ConditionalCommentStart(&'a str, Range),
-    ConditionalCommentEnd(Range),
+    ConditionalCommentEnd(Range, Range),
     Comment(&'a str, Range),
     DtdStart(&'a str, Option<ExternalId<'a>>, Range),
     EmptyDtd(&'a str, Option<ExternalId<'a>>, Range),
@@ -59,8 +59,8 @@ pub fn to_test_token(token: Result<html::Token, html::Error>) -> Token {
         Ok(html::Token::ConditionalCommentStart { condition, span }) => {
             Token::ConditionalCommentStart(condition.as_str(), span.range())
         }
-        Ok(html::Token::ConditionalCommentEnd { span }) => {
-            Token::ConditionalCommentEnd(span.range())
+        Ok(html::Token::ConditionalCommentEnd { start, span }) => {
+            Token::ConditionalCommentEnd(start.range(), span.range())
         }
         Ok(html::Token::Declaration {
             version,
@@ -0,0 +1,73 @@
+use html::resolve_url;
+
+// Examples from RFC 3986, section 5.4.
+const BASE: &str = "http://a/b/c/d;p?q";
+
+#[test]
+fn resolve_url_01() {
+    let examples = [
+        ("g:h", "g:h"),
+        ("g", "http://a/b/c/g"),
+        ("./g", "http://a/b/c/g"),
+        ("g/", "http://a/b/c/g/"),
+        ("/g", "http://a/g"),
+        ("//g", "http://g"),
+        ("?y", "http://a/b/c/d;p?y"),
+        ("g?y", "http://a/b/c/g?y"),
+        ("#s", "http://a/b/c/d;p?q#s"),
+        ("g#s", "http://a/b/c/g#s"),
+        ("g?y#s", "http://a/b/c/g?y#s"),
+        (";x", "http://a/b/c/;x"),
+        ("g;x", "http://a/b/c/g;x"),
+        ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+        ("", "http://a/b/c/d;p?q"),
+        (".", "http://a/b/c/"),
+        ("./", "http://a/b/c/"),
+        ("..", "http://a/b/"),
+        ("../", "http://a/b/"),
+        ("../g", "http://a/b/g"),
+        ("../..", "http://a/"),
+        ("../../", "http://a/"),
+        ("../../g", "http://a/g"),
+    ];
+    for (url, expected) in examples {
+        assert_eq!(resolve_url(BASE, url), expected, "{}", url);
+    }
+}
+
+#[test]
+fn resolve_url_02() {
+    let examples = [
+        ("../../../g", "http://a/g"),
+        ("../../../../g", "http://a/g"),
+        ("/./g", "http://a/g"),
+        ("/../g", "http://a/g"),
+        ("g.", "http://a/b/c/g."),
+        (".g", "http://a/b/c/.g"),
+        ("g..", "http://a/b/c/g.."),
+        ("..g", "http://a/b/c/..g"),
+        ("./../g", "http://a/b/g"),
+        ("./g/.", "http://a/b/c/g/"),
+        ("g/./h", "http://a/b/c/g/h"),
+        ("g/../h", "http://a/b/c/h"),
+        ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+        ("g;x=1/../y", "http://a/b/c/y"),
+        ("g?y/./x", "http://a/b/c/g?y/./x"),
+        ("g#s/../x", "http://a/b/c/g#s/../x"),
+        ("http:g", "http:g"),
+    ];
+    for (url, expected) in examples {
+        assert_eq!(resolve_url(BASE, url), expected, "{}", url);
+    }
+}
+
+#[test]
+fn resolve_url_03() {
+    assert_eq!(resolve_url("http://a", "g"), "http://a/g");
+    assert_eq!(
+        resolve_url("http://a/b/", " \tc\nd.html "),
+        "http://a/b/cd.html"
+    );
+    assert_eq!(resolve_url("/docs/", "../é.png"), "/é.png");
+    assert_eq!(resolve_url("", "a/b"), "a/b");
+}